@@ -0,0 +1,326 @@
+//! Enumerating the solutions of a `Solver`, with the controls that
+//! `Solver::solutions` leaves out.
+//!
+//! Solutions are projected onto whatever you pass in (a variable, a tuple of
+//! them, a grid...), so two models that only differ elsewhere count once.
+//! Enumeration can stop after a number of solutions or a time budget, report
+//! progress as it goes, and skip solutions that are the same as an earlier
+//! one under a canonical form you supply, such as a rotation of a grid.
+//!
+//! ```ignore
+//! let found = Enumerator::new(&solver, (&x, &y))
+//!     .limit(100)
+//!     .time_budget(Duration::from_secs(5))
+//!     .run();
+//! ```
+//!
+//! The time budget is enforced by interrupting Z3, and `interrupt_after` does
+//! the same for any other piece of work.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use z3::{ContextHandle, SatResult, Solvable, Solver};
+
+/// Once the time is up, `interrupt_after` interrupts Z3 this often until the
+/// work returns. An interrupt only stops the call in flight, and one that
+/// lands just before a call starts is lost.
+const INTERRUPT_EVERY: Duration = Duration::from_millis(10);
+
+/// Why enumeration stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stop {
+    /// Every solution has been found.
+    Exhausted,
+    /// The limit on the number of solutions was reached.
+    Limit,
+    /// The time budget ran out first.
+    TimeBudget,
+    /// Z3 couldn't decide whether there are more.
+    Unknown,
+}
+
+/// What an enumeration found.
+pub struct Enumeration<S> {
+    /// The solutions, in the order found, one per canonical form.
+    pub solutions: Vec<S>,
+    /// Solutions skipped because an earlier one had the same canonical form.
+    pub duplicates: usize,
+    pub elapsed: Duration,
+    pub stop: Stop,
+}
+
+/// How far an enumeration has got, as passed to a progress callback.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub solutions: usize,
+    pub duplicates: usize,
+    pub elapsed: Duration,
+}
+
+/// Maps a solution to the form duplicates share.
+type Canonical<'a, S, K> = Box<dyn Fn(&S) -> K + 'a>;
+
+/// Called with the progress so far.
+type Report<'a> = Box<dyn FnMut(&Progress) + 'a>;
+
+/// Builds and runs an enumeration over `solver`. The solver is left as it
+/// was found: everything added while enumerating is popped afterwards, and
+/// the time budget never touches the solver's own timeout.
+pub struct Enumerator<'a, T: Solvable, K = ()> {
+    solver: &'a Solver,
+    project: T,
+    limit: Option<usize>,
+    budget: Option<Duration>,
+    progress: Option<(usize, Report<'a>)>,
+    canonical: Option<Canonical<'a, T::ModelInstance, K>>,
+}
+
+impl<'a, T: Solvable> Enumerator<'a, T> {
+    /// Enumerates the distinct values of `project` across the solver's models.
+    pub fn new(solver: &'a Solver, project: T) -> Self {
+        Enumerator {
+            solver,
+            project,
+            limit: None,
+            budget: None,
+            progress: None,
+            canonical: None,
+        }
+    }
+}
+
+impl<'a, T: Solvable, K: Eq + Hash> Enumerator<'a, T, K> {
+    /// Stops after this many solutions (not counting duplicates).
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Stops once this much time has passed, including inside a check.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Calls `report` every `every` solutions.
+    pub fn progress_every(mut self, every: usize, report: impl FnMut(&Progress) + 'a) -> Self {
+        self.progress = Some((every.max(1), Box::new(report)));
+        self
+    }
+
+    /// Treats solutions with the same `canonical` form as one, keeping the
+    /// first found.
+    pub fn canonical<C: Eq + Hash>(
+        self,
+        canonical: impl Fn(&T::ModelInstance) -> C + 'a,
+    ) -> Enumerator<'a, T, C> {
+        Enumerator {
+            solver: self.solver,
+            project: self.project,
+            limit: self.limit,
+            budget: self.budget,
+            progress: self.progress,
+            canonical: Some(Box::new(canonical)),
+        }
+    }
+
+    pub fn run(mut self) -> Enumeration<T::ModelInstance> {
+        let start = Instant::now();
+        let mut solutions = Vec::new();
+        let mut duplicates = 0;
+        let mut seen = HashSet::new();
+
+        // Blocking clauses go in their own scope so they can be dropped.
+        let solver = self.solver;
+        solver.push();
+        let z3 = solver.get_context().handle();
+        let stop = interrupt_after(&z3, self.budget, || {
+            loop {
+                if self.limit.is_some_and(|limit| solutions.len() >= limit) {
+                    break Stop::Limit;
+                }
+                if self.out_of_time(start) {
+                    break Stop::TimeBudget;
+                }
+
+                match solver.check() {
+                    SatResult::Sat => {}
+                    SatResult::Unsat => break Stop::Exhausted,
+                    SatResult::Unknown if self.out_of_time(start) => break Stop::TimeBudget,
+                    SatResult::Unknown => break Stop::Unknown,
+                }
+
+                let model = solver.get_model().unwrap();
+                let Some(solution) = self.project.read_from_model(&model, true) else {
+                    if self.out_of_time(start) {
+                        break Stop::TimeBudget;
+                    }
+                    break Stop::Unknown;
+                };
+                // Rule out this projection, whatever the other variables were.
+                solver.assert(self.project.generate_constraint(&solution));
+
+                match &self.canonical {
+                    Some(canonical) if !seen.insert(canonical(&solution)) => duplicates += 1,
+                    _ => {
+                        solutions.push(solution);
+                        if let Some((every, report)) = &mut self.progress
+                            && solutions.len() % *every == 0
+                        {
+                            report(&Progress {
+                                solutions: solutions.len(),
+                                duplicates,
+                                elapsed: start.elapsed(),
+                            });
+                        }
+                    }
+                }
+            }
+        });
+        solver.pop(1);
+
+        Enumeration {
+            solutions,
+            duplicates,
+            elapsed: start.elapsed(),
+            stop,
+        }
+    }
+
+    fn out_of_time(&self, start: Instant) -> bool {
+        self.budget.is_some_and(|budget| start.elapsed() >= budget)
+    }
+}
+
+/// Runs `work`, interrupting whatever Z3 is doing in `z3`'s context once
+/// `limit` has passed, and again every so often until `work` returns. Work
+/// that makes several Z3 calls should also watch the clock itself, so it
+/// doesn't carry on between them. With no limit, `work` just runs.
+pub fn interrupt_after<R>(
+    z3: &ContextHandle,
+    limit: Option<Duration>,
+    work: impl FnOnce() -> R,
+) -> R {
+    let Some(limit) = limit else {
+        return work();
+    };
+    let (done, finished) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            let mut wait = limit;
+            while let Err(mpsc::RecvTimeoutError::Timeout) = finished.recv_timeout(wait) {
+                z3.interrupt();
+                wait = INTERRUPT_EVERY;
+            }
+        });
+        let result = work();
+        drop(done);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Enumerator, Stop};
+    use std::time::Duration;
+    use z3::ast::Int;
+    use z3::{SatResult, Solver};
+
+    // A solver whose models are the integers `lo..hi`.
+    fn range(lo: i64, hi: i64) -> (Solver, Int) {
+        let solver = Solver::new();
+        let x = Int::new_const("x");
+        solver.assert(x.ge(lo));
+        solver.assert(x.lt(hi));
+        (solver, x)
+    }
+
+    fn sorted(solutions: &[Int]) -> Vec<i64> {
+        let mut values: Vec<i64> = solutions.iter().map(|v| v.as_i64().unwrap()).collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn finds_every_solution_once() {
+        let (solver, x) = range(0, 5);
+        let found = Enumerator::new(&solver, &x).run();
+        assert_eq!(sorted(&found.solutions), vec![0, 1, 2, 3, 4]);
+        assert_eq!(found.stop, Stop::Exhausted);
+        // The blocking clauses are gone again.
+        assert_eq!(solver.check(), SatResult::Sat);
+    }
+
+    #[test]
+    fn solutions_are_projected() {
+        // y is free, but only the distinct values of x count.
+        let (solver, x) = range(0, 3);
+        let y = Int::new_const("y");
+        solver.assert(y.ge(0));
+        let found = Enumerator::new(&solver, &x).run();
+        assert_eq!(sorted(&found.solutions), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn limit_stops_early() {
+        let (solver, x) = range(0, 100);
+        let found = Enumerator::new(&solver, &x).limit(3).run();
+        assert_eq!(found.solutions.len(), 3);
+        assert_eq!(found.stop, Stop::Limit);
+    }
+
+    #[test]
+    fn canonical_forms_drop_duplicates() {
+        let (solver, x) = range(-3, 4);
+        let found = Enumerator::new(&solver, &x)
+            .canonical(|v| v.as_i64().unwrap().abs())
+            .run();
+        let mut magnitudes: Vec<i64> = found
+            .solutions
+            .iter()
+            .map(|v| v.as_i64().unwrap().abs())
+            .collect();
+        magnitudes.sort();
+        assert_eq!(magnitudes, vec![0, 1, 2, 3]);
+        assert_eq!(found.duplicates, 3);
+    }
+
+    #[test]
+    fn progress_is_reported_every_n() {
+        let (solver, x) = range(0, 5);
+        let mut reports = Vec::new();
+        let found = Enumerator::new(&solver, &x)
+            .progress_every(2, |progress| reports.push(progress.solutions))
+            .run();
+        assert_eq!(found.solutions.len(), 5);
+        assert_eq!(reports, vec![2, 4]);
+    }
+
+    #[test]
+    fn time_budget_interrupts_a_hard_check() {
+        // No positive cubes add up like this, and Z3 can't prove it quickly.
+        let solver = Solver::new();
+        let (x, y, z) = (
+            Int::new_const("x"),
+            Int::new_const("y"),
+            Int::new_const("z"),
+        );
+        for v in [&x, &y, &z] {
+            solver.assert(v.gt(0));
+        }
+        solver.assert((&x * &x * &x + &y * &y * &y).eq(&z * &z * &z));
+
+        let found = Enumerator::new(&solver, (&x, &y, &z))
+            .time_budget(Duration::from_millis(200))
+            .run();
+        assert_eq!(found.stop, Stop::TimeBudget);
+        assert!(
+            found.elapsed < Duration::from_secs(5),
+            "{:?}",
+            found.elapsed
+        );
+    }
+}
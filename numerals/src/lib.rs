@@ -0,0 +1,167 @@
+//! Exact values out of Z3 models.
+//!
+//! `as_i64()` gives up on anything outside 64 bits, and real inputs (AoC
+//! 2023 day 24 answers are around 10^15, with far larger products on the way)
+//! get there quickly. These helpers read the numeral Z3 prints instead, so
+//! integers of any size and rationals come back exactly.
+
+use num::{ToPrimitive, Zero};
+use std::iter::Peekable;
+use z3::Model;
+use z3::ast::{Int, Real};
+
+pub use num::{BigInt, BigRational};
+
+/// The exact value of an integer numeral.
+///
+/// Panics if `numeral` isn't a numeral, e.g. a term the model couldn't
+/// evaluate.
+pub fn int(numeral: &Int) -> BigInt {
+    let value = parse(numeral);
+    assert!(value.is_integer(), "Not an integer: {}", numeral);
+    value.to_integer()
+}
+
+/// The exact value of a real numeral, as a fraction in lowest terms.
+pub fn real(numeral: &Real) -> BigRational {
+    parse(numeral)
+}
+
+/// Evaluates an integer term in `model`, with model completion.
+pub fn eval_int(model: &Model, term: &Int) -> BigInt {
+    int(&model.eval(term, true).unwrap())
+}
+
+/// Evaluates a real term in `model`, with model completion.
+pub fn eval_real(model: &Model, term: &Real) -> BigRational {
+    real(&model.eval(term, true).unwrap())
+}
+
+/// Like `eval_int`, for values the caller needs as an `i64`. Panics with the
+/// value if it doesn't fit, rather than with a bare `unwrap`.
+pub fn eval_i64(model: &Model, term: &Int) -> i64 {
+    let value = eval_int(model, term);
+    value
+        .to_i64()
+        .unwrap_or_else(|| panic!("{} = {} doesn't fit in an i64", term, value))
+}
+
+fn parse(numeral: &impl std::fmt::Display) -> BigRational {
+    let text = numeral.to_string();
+    value(&text).unwrap_or_else(|| panic!("Not a numeral: {}", text))
+}
+
+// The value of a numeral as Z3 prints it, or None if `text` isn't one.
+fn value(text: &str) -> Option<BigRational> {
+    let spaced = text.replace('(', " ( ").replace(')', " ) ");
+    let mut tokens = spaced.split_whitespace().peekable();
+    let value = term(&mut tokens)?;
+    tokens.next().is_none().then_some(value)
+}
+
+// Z3 prints numerals as SMT-LIB terms: `42` and `(- 42)` for integers, and
+// `2.0`, `(/ 7.0 2.0)` or `(- (/ 7.0 2.0))` for reals.
+fn term<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Option<BigRational> {
+    let token = tokens.next()?;
+    if token != "(" {
+        return decimal(token);
+    }
+
+    let op = tokens.next()?;
+    let mut args = Vec::new();
+    while *tokens.peek()? != ")" {
+        args.push(term(tokens)?);
+    }
+    tokens.next();
+
+    match (op, &args[..]) {
+        ("-", [x]) => Some(-x),
+        ("/", [a, b]) if !b.is_zero() => Some(a / b),
+        _ => None,
+    }
+}
+
+// `42`, or `42.0` and `0.25` as Z3 writes reals.
+fn decimal(token: &str) -> Option<BigRational> {
+    let (whole, fraction) = token.split_once('.').unwrap_or((token, ""));
+    if whole.is_empty()
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits: BigInt = format!("{}{}", whole, fraction).parse().ok()?;
+    let scale = num::pow(BigInt::from(10), fraction.len());
+    Some(BigRational::new(digits, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BigInt, BigRational, parse, value};
+
+    fn ratio(numer: i64, denom: i64) -> Option<BigRational> {
+        Some(BigRational::new(numer.into(), denom.into()))
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(value("42"), ratio(42, 1));
+        assert_eq!(value("(- 42)"), ratio(-42, 1));
+        assert_eq!(value("0"), ratio(0, 1));
+    }
+
+    #[test]
+    fn reals() {
+        assert_eq!(value("2.0"), ratio(2, 1));
+        assert_eq!(value("0.25"), ratio(1, 4));
+        assert_eq!(value("(/ 7.0 2.0)"), ratio(7, 2));
+        assert_eq!(value("(- (/ 7.0 2.0))"), ratio(-7, 2));
+    }
+
+    #[test]
+    fn beyond_i64() {
+        let big: BigInt = "123456789012345678901234567890".parse().unwrap();
+        assert_eq!(
+            value("123456789012345678901234567890"),
+            Some(BigRational::from_integer(big.clone()))
+        );
+        assert_eq!(
+            value("(- 123456789012345678901234567890)"),
+            Some(BigRational::from_integer(-big))
+        );
+        // One past i64::MIN.
+        let below: BigInt = "-9223372036854775809".parse().unwrap();
+        assert_eq!(
+            value("(- 9223372036854775809)"),
+            Some(BigRational::from_integer(below))
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        for text in [
+            "",
+            "x",
+            "-42",
+            ".5",
+            "1.2.3",
+            "4 2",
+            "42)",
+            "(- 42",
+            "(+ 1 2)",
+            "(- 1 2)",
+            "(/ 1.0 0.0)",
+            "(/ 1.0)",
+        ] {
+            assert_eq!(value(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a numeral: (x)")]
+    fn parse_panics_on_malformed() {
+        parse(&"(x)");
+    }
+}
@@ -79,9 +79,9 @@ fn solve_optimization() {
         let model = optimizer.get_model().unwrap();
 
         // Get variables from the model.
-        let x_val = model.eval(&x, true).unwrap().as_i64().unwrap();
-        let y_val = model.eval(&y, true).unwrap().as_i64().unwrap();
-        let sum_val = model.eval(&sum, true).unwrap().as_i64().unwrap();
+        let x_val = numerals::eval_int(&model, &x);
+        let y_val = numerals::eval_int(&model, &y);
+        let sum_val = numerals::eval_int(&model, &sum);
 
         println!("Optimal Solution:");
         println!("  x = {}", x_val);
@@ -1,5 +1,12 @@
-use z3::ast::Int;
-use z3::{SatResult, Solver};
+use enumeration::Enumerator;
+use numerals::BigRational;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::process;
+use std::time::{Duration, Instant};
+use z3::ast::{Int, Real};
+use z3::{SatResult, Solver, StatisticsValue, Tactic};
 
 /// Example 1: Basic Solver With Multiple Solutions
 /// Goal: Find all Integers x and y such that:
@@ -21,21 +28,175 @@ fn demonstrate_multiple_solutions() {
     solver.assert(y.gt(10));
     solver.assert((&x + &y).eq(25));
 
-    // Use the `solutions` iterator to automatically find all satisfying assignments
-    // for the tuple (x, y). The second argument `true` enables model completion.
+    // Enumerate the distinct (x, y) pairs. `Solver::solutions` would do on
+    // its own here, but real problems need a cap on how many we want and how
+    // long we're willing to wait, so we go through an `Enumerator`.
+    let found = Enumerator::new(&solver, (&x, &y))
+        .limit(100)
+        .time_budget(Duration::from_secs(5))
+        .run();
+    for (count, (x_sol, y_sol)) in found.solutions.iter().enumerate() {
+        // The solutions are new AST nodes representing the concrete values from the model
+        let x_val = numerals::int(x_sol);
+        let y_val = numerals::int(y_sol);
+
+        println!("Solution #{}: x = {}, y = {}", count + 1, x_val, y_val);
+    }
+    println!(
+        "Found {} total solutions in {:?} ({:?}).",
+        found.solutions.len(),
+        found.elapsed,
+        found.stop
+    );
+}
+
+/// A hailstone from Advent of Code 2023 Day 24: where it starts and how far
+/// it moves each nanosecond.
+struct Hailstone {
+    px: i64,
+    py: i64,
+    pz: i64,
+    vx: i64,
+    vy: i64,
+    vz: i64,
+}
+
+impl Hailstone {
+    /// Parses a line of the puzzle input: `px, py, pz @ vx, vy, vz`.
+    fn parse(line: &str) -> Result<Hailstone, String> {
+        let nums = line
+            .split(['@', ','])
+            .map(|s| {
+                s.trim()
+                    .parse()
+                    .map_err(|_| format!("{:?} is not a number", s.trim()))
+            })
+            .collect::<Result<Vec<i64>, String>>()?;
+        match nums[..] {
+            [px, py, pz, vx, vy, vz] => Ok(Hailstone {
+                px,
+                py,
+                pz,
+                vx,
+                vy,
+                vz,
+            }),
+            _ => Err(format!("expected 6 numbers, found {}", nums.len())),
+        }
+    }
+}
+
+/// The example input from the puzzle text, used when no input file is given.
+const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3
+";
+
+/// The test area for part 1: the example's, and the real puzzle's.
+const EXAMPLE_AREA: (i64, i64) = (7, 27);
+const PUZZLE_AREA: (i64, i64) = (200_000_000_000_000, 400_000_000_000_000);
+
+/// Parses every non-blank line, or says which line (counting from 1) is
+/// malformed.
+fn parse_hailstones(input: &str) -> Result<Vec<Hailstone>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| Hailstone::parse(l).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Example 2: Advent of Code 2023 Day 24 (Part 1)
+/// Goal: Count the pairs of hailstones whose paths cross inside the test area,
+/// looking only at X and Y and ignoring when each stone gets there.
+///
+/// Math:
+///   Paths a and b cross if there are times t, s >= 0 such that:
+///     PosA + VelA * t = PosB + VelB * s
+///   for X and Y, with the crossing point inside the area. The crossing is
+///   rarely at a whole number, so we use `Real` rather than `Int`.
+///
+/// That's one Z3 check per pair: 10 for the example, but about 45,000 for a
+/// real 300-stone input, so expect the real thing to take a while.
+fn solve_aoc_day24_part1(hailstones: &[Hailstone], (min, max): (i64, i64)) {
+    println!("\n--- Advent of Code 2023 Day 24 (Part 1) ---");
+
+    let solver = Solver::new();
+    let t = Real::new_const("t");
+    let s = Real::new_const("s");
+    // Real::from_rational only takes 32-bit values, so puzzle-sized numbers
+    // go in as an Int and get converted.
+    let real = |n: i64| Real::from_int(&Int::from_i64(n));
+    let zero = real(0);
+    let min = real(min);
+    let max = real(max);
+
+    // Position + Velocity * Time, for one coordinate
+    let at = |p: i64, v: i64, time: &Real| &real(p) + &(time * &real(v));
+
+    let pairs = hailstones.len() * hailstones.len().saturating_sub(1) / 2;
+    println!("Checking {} pairs, one Z3 check each...", pairs);
+
     let mut count = 0;
-    for (x_sol, y_sol) in solver.solutions((&x, &y), true) {
-        count += 1;
-        // The iterator returns new AST nodes representing the concrete values from the model
-        let x_val = x_sol.as_i64().unwrap();
-        let y_val = y_sol.as_i64().unwrap();
+    for (i, a) in hailstones.iter().enumerate() {
+        for b in &hailstones[i + 1..] {
+            // Push a scope for this pair, so the next pair starts clean.
+            solver.push();
+            solver.assert(t.ge(&zero));
+            solver.assert(s.ge(&zero));
+
+            let x = at(a.px, a.vx, &t);
+            solver.assert(x.eq(at(b.px, b.vx, &s)));
+            solver.assert(x.ge(&min));
+            solver.assert(x.le(&max));
 
-        println!("Solution #{}: x = {}, y = {}", count, x_val, y_val);
+            let y = at(a.py, a.vy, &t);
+            solver.assert(y.eq(at(b.py, b.vy, &s)));
+            solver.assert(y.ge(&min));
+            solver.assert(y.le(&max));
+
+            if solver.check() == SatResult::Sat {
+                count += 1;
+            }
+            solver.pop(1);
+        }
     }
-    println!("Found {} total solutions.", count);
+    println!("Paths crossing inside the test area: {}", count);
+}
+
+/// Which number type the part 2 model is built over.
+#[derive(Clone, Copy, PartialEq)]
+enum Formulation {
+    Int,
+    Real,
 }
 
-/// Example 2: Advent of Code 2023 Day 24 (Part 2)
+impl Formulation {
+    /// The tactic we build the solver from unless told otherwise. The Int
+    /// model keeps `Solver::new()`, which picks its own strategy; the Real
+    /// model names `qfnra-nlsat` so the report can say what ran.
+    fn default_tactic(self) -> Option<&'static str> {
+        match self {
+            Formulation::Int => None,
+            Formulation::Real => Some("qfnra-nlsat"),
+        }
+    }
+}
+
+impl fmt::Display for Formulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Formulation::Int => write!(f, "Int"),
+            Formulation::Real => write!(f, "Real"),
+        }
+    }
+}
+
+/// Example 3: Advent of Code 2023 Day 24 (Part 2)
 /// Goal: Find the initial position and velocity of a rock that will collide with
 /// all hailstones at some point in time.
 ///
@@ -43,67 +204,93 @@ fn demonstrate_multiple_solutions() {
 ///   For each hailstone i, there exists a time t_i >= 0 such that:
 ///     RockPos + RockVel * t_i = HailPos_i + HailVel_i * t_i
 ///   
-///   This gives us 3 equations (x, y, z) for each hailstone.
-fn solve_aoc_day24() {
-    println!("\n--- Advent of Code 2023 Day 24 (Part 2) ---");
-
-    // Setup our problem.
-    struct Hailstone {
-        px: i64,
-        py: i64,
-        pz: i64,
-        vx: i64,
-        vy: i64,
-        vz: i64,
-    }
-
-    // A subset of the example input from AoC
-    let hailstones = [
-        Hailstone {
-            px: 19,
-            py: 13,
-            pz: 30,
-            vx: -2,
-            vy: 1,
-            vz: -2,
-        },
-        Hailstone {
-            px: 18,
-            py: 19,
-            pz: 22,
-            vx: -1,
-            vy: -1,
-            vz: -2,
-        },
-        Hailstone {
-            px: 20,
-            py: 25,
-            pz: 34,
-            vx: -2,
-            vy: -2,
-            vz: -4,
-        },
-        Hailstone {
-            px: 12,
-            py: 31,
-            pz: 28,
-            vx: -1,
-            vy: -2,
-            vz: -1,
-        },
-        Hailstone {
-            px: 20,
-            py: 19,
-            pz: 15,
-            vx: 1,
-            vy: -5,
-            vz: -3,
-        },
-    ];
-
-    // Create our solver.
-    let solver = Solver::new();
+///   This gives us 3 equations (x, y, z) for each hailstone. Three hailstones
+///   give 9 equations in 9 unknowns, which pins the rock down, so the rest of
+///   the input only slows the solver down.
+///
+/// The puzzle wants whole numbers, but the same equations can be posed over
+/// `Real`, which Z3 hands to a different decision procedure. Nothing then
+/// forces the answer to be integral, so we check.
+fn solve_aoc_day24(hailstones: &[Hailstone], formulation: Formulation, tactic: Option<&str>) {
+    println!(
+        "\n--- Advent of Code 2023 Day 24 (Part 2, {} model) ---",
+        formulation
+    );
+
+    let hailstones = &hailstones[..hailstones.len().min(3)];
 
+    // Create our solver, from a named tactic if there is one.
+    let (solver, strategy) = match tactic.or(formulation.default_tactic()) {
+        Some(tactic) => (Tactic::new(tactic).solver(), format!("tactic {}", tactic)),
+        None => (Solver::new(), "Z3's default solver".to_string()),
+    };
+
+    println!("Solver checking with {}...", strategy);
+    let start = Instant::now();
+    let rock = match formulation {
+        Formulation::Int => rock_over_int(&solver, hailstones),
+        Formulation::Real => rock_over_real(&solver, hailstones),
+    };
+    println!("Took {:?}", start.elapsed());
+    println!("Search: {}", search_summary(&solver));
+
+    let rock = match rock {
+        Ok(rock) => rock,
+        Err(SatResult::Unknown) => {
+            let reason = solver.get_reason_unknown().unwrap_or_default();
+            println!("Z3 gave up: {}", reason);
+            return;
+        }
+        Err(_) => {
+            println!("No solution found.");
+            return;
+        }
+    };
+
+    // Position then velocity
+    let [x, y, z, vx, vy, vz] = &rock[..] else {
+        unreachable!()
+    };
+    println!("Found Rock Trajectory!");
+    println!("Position: ({}, {}, {})", x, y, z);
+    println!("Velocity: ({}, {}, {})", vx, vy, vz);
+    println!("Sum of coordinates: {}", x + y + z);
+
+    let fractional: Vec<&BigRational> = rock.iter().filter(|v| !v.is_integer()).collect();
+    if !fractional.is_empty() {
+        println!(
+            "Warning: the {} model returned non-integral values ({}), so this is not a valid puzzle answer.",
+            formulation,
+            fractional
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+/// What the check cost, from the solver's statistics. Z3's default solver
+/// doesn't say which strategy it picked, but the names give a hint: they
+/// start with `sat` when it bit-blasted the problem down to the SAT core.
+fn search_summary(solver: &Solver) -> String {
+    let stats = solver.get_statistics();
+    let parts: Vec<String> = stats
+        .entries()
+        .filter(|e| {
+            e.key.ends_with("conflicts") || e.key.ends_with("decisions") || e.key == "max memory"
+        })
+        .map(|e| match e.value {
+            StatisticsValue::UInt(v) => format!("{} {}", e.key, v),
+            StatisticsValue::Double(v) => format!("{} {:.2} MB", e.key, v),
+        })
+        .collect();
+    parts.join(", ")
+}
+
+/// The part 2 model over `Int`. Returns the rock's position and velocity,
+/// or the check result if it isn't Sat.
+fn rock_over_int(solver: &Solver, hailstones: &[Hailstone]) -> Result<Vec<BigRational>, SatResult> {
     // Define our variables.
     let rpx = Int::new_const("rpx");
     let rpy = Int::new_const("rpy");
@@ -141,30 +328,140 @@ fn solve_aoc_day24() {
         solver.assert((&rpz + &rvz * &t).eq(&(&hpz + &hvz * &t)));
     }
 
-    println!("Solver checking... (this might take a moment)");
-
     // Check and Solve
-    if solver.check() == SatResult::Sat {
-        let model = solver.get_model().unwrap();
-
-        // Extract Results
-        let x = model.eval(&rpx, true).unwrap().as_i64().unwrap();
-        let y = model.eval(&rpy, true).unwrap().as_i64().unwrap();
-        let z = model.eval(&rpz, true).unwrap().as_i64().unwrap();
-        let vx = model.eval(&rvx, true).unwrap().as_i64().unwrap();
-        let vy = model.eval(&rvy, true).unwrap().as_i64().unwrap();
-        let vz = model.eval(&rvz, true).unwrap().as_i64().unwrap();
-
-        println!("Found Rock Trajectory!");
-        println!("Position: ({}, {}, {})", x, y, z);
-        println!("Velocity: ({}, {}, {})", vx, vy, vz);
-        println!("Sum of coordinates: {}", x + y + z);
-    } else {
-        println!("No solution found.");
+    match solver.check() {
+        SatResult::Sat => {}
+        result => return Err(result),
     }
+    let model = solver.get_model().unwrap();
+    let rock = [rpx, rpy, rpz, rvx, rvy, rvz]
+        .iter()
+        .map(|v| BigRational::from_integer(numerals::eval_int(&model, v)))
+        .collect();
+    Ok(rock)
+}
+
+/// The same model over `Real`.
+fn rock_over_real(
+    solver: &Solver,
+    hailstones: &[Hailstone],
+) -> Result<Vec<BigRational>, SatResult> {
+    let real = |n: i64| Real::from_int(&Int::from_i64(n));
+
+    let rpx = Real::new_const("rpx");
+    let rpy = Real::new_const("rpy");
+    let rpz = Real::new_const("rpz");
+    let rvx = Real::new_const("rvx");
+    let rvy = Real::new_const("rvy");
+    let rvz = Real::new_const("rvz");
+
+    for (i, h) in hailstones.iter().enumerate() {
+        let t = Real::new_const(format!("t_{}", i));
+        solver.assert(t.ge(real(0)));
+
+        solver.assert((&rpx + &rvx * &t).eq(&real(h.px) + &real(h.vx) * &t));
+        solver.assert((&rpy + &rvy * &t).eq(&real(h.py) + &real(h.vy) * &t));
+        solver.assert((&rpz + &rvz * &t).eq(&real(h.pz) + &real(h.vz) * &t));
+    }
+
+    match solver.check() {
+        SatResult::Sat => {}
+        result => return Err(result),
+    }
+    let model = solver.get_model().unwrap();
+    let rock = [rpx, rpy, rpz, rvx, rvy, rvz]
+        .iter()
+        .map(|v| numerals::eval_real(&model, v))
+        .collect();
+    Ok(rock)
 }
 
 fn main() {
+    // Usage: part_02_solver [--real | --both] [--tactic NAME]
+    //                       [input file] [area min] [area max]
+    let mut formulations = vec![Formulation::Int];
+    let mut tactic = None;
+    let mut args = Vec::new();
+    let mut rest = env::args().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--real" => formulations = vec![Formulation::Real],
+            "--both" => formulations = vec![Formulation::Int, Formulation::Real],
+            "--tactic" => tactic = Some(rest.next().expect("--tactic needs a tactic name")),
+            _ => args.push(arg),
+        }
+    }
+
+    if let Some(name) = &tactic
+        && !Tactic::list_all()
+            .iter()
+            .any(|known| known.as_deref() == Ok(name.as_str()))
+    {
+        eprintln!("Unknown tactic: {}", name);
+        eprintln!(
+            "Z3 knows: {}",
+            Tactic::list_all()
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        process::exit(1);
+    }
+
     demonstrate_multiple_solutions();
-    solve_aoc_day24();
+
+    let (input, mut area) = match args.first() {
+        Some(path) => (
+            fs::read_to_string(path).expect("Failed to read input"),
+            PUZZLE_AREA,
+        ),
+        None => (EXAMPLE.to_string(), EXAMPLE_AREA),
+    };
+    if let [_, min, max] = &args[..] {
+        area = (
+            min.parse().expect("Invalid area minimum"),
+            max.parse().expect("Invalid area maximum"),
+        );
+    }
+    let hailstones = match parse_hailstones(&input) {
+        Ok(hailstones) => hailstones,
+        Err(problem) => {
+            eprintln!("Invalid hailstone input: {}", problem);
+            process::exit(1);
+        }
+    };
+
+    solve_aoc_day24_part1(&hailstones, area);
+    for formulation in formulations {
+        solve_aoc_day24(&hailstones, formulation, tactic.as_deref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EXAMPLE, parse_hailstones};
+
+    #[test]
+    fn parses_the_example() {
+        let hailstones = parse_hailstones(EXAMPLE).unwrap();
+        assert_eq!(hailstones.len(), 5);
+        let h = &hailstones[4];
+        assert_eq!((h.px, h.py, h.pz), (20, 19, 15));
+        assert_eq!((h.vx, h.vy, h.vz), (1, -5, -3));
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_by_number() {
+        // Blank lines are skipped but still counted.
+        let input = "19, 13, 30 @ -2, 1, -2\n\n18, 19 @ -1, -1, -2\n";
+        assert_eq!(
+            parse_hailstones(input).err().as_deref(),
+            Some("line 3: expected 6 numbers, found 5")
+        );
+        assert_eq!(
+            parse_hailstones("1, 2, x @ 4, 5, 6").err().as_deref(),
+            Some("line 1: \"x\" is not a number")
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use numerals::BigInt;
 use z3::ast::{Bool, Int};
 use z3::{Optimize, SatResult};
 
@@ -90,10 +91,13 @@ fn demonstrate_maximization() {
                 );
             }
         }
-        println!("  Total Value: {}", model.eval(&total_value, true).unwrap());
+        println!(
+            "  Total Value: {}",
+            numerals::eval_int(&model, &total_value)
+        );
         println!(
             "  Total Weight: {}",
-            model.eval(&total_weight, true).unwrap()
+            numerals::eval_int(&model, &total_weight)
         );
     } else {
         println!("UNSAT");
@@ -150,7 +154,7 @@ fn solve_aoc_day10() {
         },
     ];
 
-    let mut total_presses_all_machines = 0;
+    let mut total_presses_all_machines = BigInt::from(0);
 
     for (m_idx, machine) in machines.iter().enumerate() {
         // Create a fresh optimizer for each machine
@@ -192,7 +196,7 @@ fn solve_aoc_day10() {
         // Solve
         if opt.check(&[]) == SatResult::Sat {
             let model = opt.get_model().unwrap();
-            let min_presses = model.eval(&sum_presses, true).unwrap().as_i64().unwrap();
+            let min_presses = numerals::eval_int(&model, &sum_presses);
             println!("Machine {}: Min presses = {}", m_idx + 1, min_presses);
             total_presses_all_machines += min_presses;
         } else {
@@ -236,7 +240,7 @@ fn solve_meeting_schedule(scenario: &str, preferences: &[Preference], explanatio
     // Z3 automatically maximizes satisfied weights (by minimizing violations)
     if opt.check(&[]) == SatResult::Sat {
         let model = opt.get_model().unwrap();
-        let selected_time = model.eval(&time, true).unwrap().as_i64().unwrap();
+        let selected_time = numerals::eval_int(&model, &time);
         println!("  Selected Time: {} AM", selected_time);
         println!("  {}", explanation);
     }
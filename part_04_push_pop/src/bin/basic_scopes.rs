@@ -40,9 +40,9 @@ fn demonstrate_basic_scopes() {
         println!("SAT");
         println!(
             "   Solution: x = {}, y = {}, z = {}",
-            model.eval(&x, true).unwrap(),
-            model.eval(&y, true).unwrap(),
-            model.eval(&z, true).unwrap()
+            numerals::eval_int(&model, &x),
+            numerals::eval_int(&model, &y),
+            numerals::eval_int(&model, &z)
         );
     } else {
         println!("UNSAT");
@@ -69,9 +69,9 @@ fn demonstrate_basic_scopes() {
         println!("SAT");
         println!(
             "   Solution: x = {}, y = {}, z = {}",
-            model.eval(&x, true).unwrap(),
-            model.eval(&y, true).unwrap(),
-            model.eval(&z, true).unwrap()
+            numerals::eval_int(&model, &x),
+            numerals::eval_int(&model, &y),
+            numerals::eval_int(&model, &z)
         );
     } else {
         println!("UNSAT");
@@ -11,6 +11,21 @@ struct Bot {
     r: i64,
 }
 
+impl Bot {
+    // Parses a line of the original puzzle input: `pos=<x,y,z>, r=N`.
+    fn parse(line: &str) -> Self {
+        let nums: Vec<i64> = line
+            .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().expect("Invalid number in nanobot input"))
+            .collect();
+        match nums[..] {
+            [x, y, z, r] => Self { x, y, z, r },
+            _ => panic!("Invalid nanobot line: {}", line),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct InputData {
     bots: Vec<Bot>,
@@ -18,17 +33,49 @@ struct InputData {
 
 fn get_input() -> Vec<Bot> {
     let args: Vec<String> = env::args().collect();
-    let reader: Box<dyn Read> = if args.len() > 1 {
+    let mut reader: Box<dyn Read> = if args.len() > 1 {
         Box::new(File::open(&args[1]).expect("Failed to open file"))
     } else {
         Box::new(io::stdin())
     };
 
-    let data: InputData = serde_json::from_reader(reader).expect("Failed to parse JSON input");
-    data.bots
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .expect("Failed to read input");
+
+    // JSON from the course inputs, or the raw puzzle text one bot per line.
+    if input.trim_start().starts_with('{') {
+        let data: InputData = serde_json::from_str(&input).expect("Failed to parse JSON input");
+        data.bots
+    } else {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Bot::parse)
+            .collect()
+    }
+}
+
+// AoC part 1: how many bots are in range of the one with the largest radius.
+// No solver needed, but a handy check that the input was read correctly.
+fn strongest_in_range(bots: &[Bot]) -> usize {
+    let Some(strongest) = bots.iter().max_by_key(|b| b.r) else {
+        return 0;
+    };
+    bots.iter()
+        .filter(|b| {
+            (b.x - strongest.x).abs() + (b.y - strongest.y).abs() + (b.z - strongest.z).abs()
+                <= strongest.r
+        })
+        .count()
 }
 
 fn main() {
     let bots = get_input();
+    println!(
+        "In range of the strongest bot: {}",
+        strongest_in_range(&bots)
+    );
     // YOUR CODE HERE
 }
@@ -0,0 +1,564 @@
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::Deserialize;
+use std::env;
+use std::fs::File;
+use std::io::{self, Stdout};
+use std::ops::{BitAnd, BitOr};
+use std::time::Duration;
+use z3::{
+    Params, SatResult, Solver,
+    ast::{Ast, Bool, Int},
+};
+
+#[derive(Deserialize, Debug)]
+struct NonogramPuzzle {
+    rows: usize,
+    cols: usize,
+    row_clues: Vec<Vec<usize>>,
+    col_clues: Vec<Vec<usize>>,
+}
+
+fn get_input() -> NonogramPuzzle {
+    // The terminal is ours while playing, so the puzzle has to come from a file.
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "inputs/nonogram_smiley.json".to_string());
+    let file = File::open(&path).expect("Failed to open file");
+    serde_json::from_reader(file).expect("Failed to parse JSON input")
+}
+
+// --- Z3 Solver Module ---
+
+// A row or column of the puzzle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LineId {
+    Row(usize),
+    Col(usize),
+}
+
+struct NonogramSolver {
+    solver: Solver,
+    grid: Vec<Vec<Bool>>,
+    // One tracker per line so the unsat core can tell us which clue broke.
+    line_trackers: Vec<(Bool, LineId)>,
+}
+
+impl NonogramSolver {
+    fn new(puzzle: &NonogramPuzzle) -> Self {
+        let solver = Solver::new();
+
+        // Ask Z3 for a small core so we only highlight the lines that matter.
+        let mut params = Params::new();
+        params.set_bool("core.minimize", true);
+        solver.set_params(&params);
+
+        let grid: Vec<Vec<Bool>> = (0..puzzle.rows)
+            .map(|r| {
+                (0..puzzle.cols)
+                    .map(|c| Bool::new_const(format!("g_{}_{}", r, c)))
+                    .collect()
+            })
+            .collect();
+
+        let mut line_trackers = Vec::new();
+        for (r, clues) in puzzle.row_clues.iter().enumerate() {
+            let line: Vec<&Bool> = grid[r].iter().collect();
+            let tracker = Bool::new_const(format!("row_{}", r));
+            let constraints = constrain_line(&line, clues, format!("r{}", r).as_str());
+            solver.assert_and_track(Bool::and(&constraints), &tracker);
+            line_trackers.push((tracker, LineId::Row(r)));
+        }
+        for (c, clues) in puzzle.col_clues.iter().enumerate() {
+            let line: Vec<&Bool> = (0..puzzle.rows).map(|r| &grid[r][c]).collect();
+            let tracker = Bool::new_const(format!("col_{}", c));
+            let constraints = constrain_line(&line, clues, format!("c{}", c).as_str());
+            solver.assert_and_track(Bool::and(&constraints), &tracker);
+            line_trackers.push((tracker, LineId::Col(c)));
+        }
+
+        Self {
+            solver,
+            grid,
+            line_trackers,
+        }
+    }
+
+    // Check the user's cells in a fresh scope. Every user cell is tracked, so
+    // on UNSAT we get back the lines and cells that conflict.
+    fn check(&self, cells: &[Vec<CellState>]) -> (SatResult, Vec<LineId>, Vec<String>) {
+        self.solver.push();
+
+        let mut cell_trackers = Vec::new();
+        for (r, row) in cells.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let (filled, label) = match cell {
+                    CellState::Unknown => continue,
+                    CellState::Filled => (true, "#"),
+                    CellState::Empty => (false, "x"),
+                };
+                let tracker = Bool::new_const(format!("cell_{}_{}", r, c));
+                self.solver
+                    .assert_and_track(self.grid[r][c].eq(Bool::from_bool(filled)), &tracker);
+                cell_trackers.push((tracker, format!("({},{})={}", r, c, label)));
+            }
+        }
+
+        let result = self.solver.check();
+        let (lines, cells) = if result == SatResult::Unsat {
+            let core = self.solver.get_unsat_core();
+            let lines = self
+                .line_trackers
+                .iter()
+                .filter(|(t, _)| core.contains(t))
+                .map(|(_, id)| *id)
+                .collect();
+            let cells = cell_trackers
+                .into_iter()
+                .filter(|(t, _)| core.contains(t))
+                .map(|(_, label)| label)
+                .collect();
+            (lines, cells)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        self.solver.pop(1);
+        (result, lines, cells)
+    }
+}
+
+// Same model as the nonogram challenge, but the constraints are returned so
+// the caller can track the whole line under a single name.
+fn constrain_line(line: &[&Bool], clues: &[usize], prefix: &str) -> Vec<Bool> {
+    let cells_len = line.len() as i64;
+    let mut constraints = Vec::new();
+
+    // Create start position variables for each block
+    let starts: Vec<Int> = (0..clues.len())
+        .map(|i| Int::new_const(format!("{}_s_{}", prefix, i).as_str()))
+        .collect();
+
+    // Constrain start positions for each block
+    for (i, (start, &block_len)) in starts.iter().zip(clues.iter()).enumerate() {
+        let len = block_len as i64;
+
+        constraints.push(start.ge(0));
+        constraints.push((start + len).le(cells_len));
+
+        // Ensure blocks don't overlap (at least 1 gap between blocks)
+        if i > 0 {
+            let prev_len = clues[i - 1] as i64;
+            constraints.push(start.ge(&(&starts[i - 1] + prev_len + 1)));
+        }
+    }
+
+    // Each cell is filled exactly when some block covers it.
+    for (j, cell) in line.iter().enumerate() {
+        let j_int = Int::from_i64(j as i64);
+        let covered = starts
+            .iter()
+            .zip(clues.iter())
+            .map(|(start, &block_len)| {
+                let len = block_len as i64;
+                start.le(&j_int).bitand(&j_int.lt(&(start + len)))
+            })
+            .reduce(|acc, cond| acc.bitor(&cond))
+            .unwrap_or_else(|| Bool::from_bool(false));
+
+        constraints.push(Ast::eq(*cell, &covered));
+    }
+
+    constraints
+}
+
+// --- Application State ---
+
+#[derive(Clone, Copy, PartialEq)]
+enum CellState {
+    Unknown,
+    Filled,
+    Empty,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GameState {
+    Playing,
+    Error, // UNSAT state, the highlighted clues conflict with the board
+    Solved,
+}
+
+struct App {
+    puzzle: NonogramPuzzle,
+    cells: Vec<Vec<CellState>>,
+    cursor: (usize, usize), // (row, col)
+    state: GameState,
+    error_info: Option<String>,
+    conflicts: Vec<LineId>,
+
+    solver: NonogramSolver,
+}
+
+impl App {
+    fn new(puzzle: NonogramPuzzle) -> Self {
+        let solver = NonogramSolver::new(&puzzle);
+        Self {
+            cells: vec![vec![CellState::Unknown; puzzle.cols]; puzzle.rows],
+            cursor: (0, 0),
+            state: GameState::Playing,
+            error_info: None,
+            conflicts: Vec::new(),
+            solver,
+            puzzle,
+        }
+    }
+
+    fn on_key(&mut self, key: KeyCode) -> bool {
+        // Quit
+        if let KeyCode::Char('q') | KeyCode::Esc = key {
+            return true;
+        }
+
+        // Navigation. Unlike the sudoku we keep editing open during a
+        // conflict, since any cell in the highlighted lines may be the culprit.
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.cursor.0 = self.cursor.0.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor.0 = (self.cursor.0 + 1).min(self.puzzle.rows - 1);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.cursor.1 = self.cursor.1.saturating_sub(1);
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.cursor.1 = (self.cursor.1 + 1).min(self.puzzle.cols - 1);
+            }
+            // Space cycles Unknown -> Filled -> Empty -> Unknown
+            KeyCode::Char(' ') => {
+                let next = match self.cells[self.cursor.0][self.cursor.1] {
+                    CellState::Unknown => CellState::Filled,
+                    CellState::Filled => CellState::Empty,
+                    CellState::Empty => CellState::Unknown,
+                };
+                self.set_cell(next);
+            }
+            KeyCode::Char('f') => self.set_cell(CellState::Filled),
+            KeyCode::Char('x') => self.set_cell(CellState::Empty),
+            KeyCode::Backspace | KeyCode::Delete => self.set_cell(CellState::Unknown),
+            _ => {}
+        }
+
+        false
+    }
+
+    fn set_cell(&mut self, value: CellState) {
+        let (r, c) = self.cursor;
+        self.cells[r][c] = value;
+
+        // Check Validity
+        let (result, lines, cells) = self.solver.check(&self.cells);
+        match result {
+            SatResult::Sat => {
+                self.error_info = None;
+                self.conflicts.clear();
+                self.state = if self.is_solved() {
+                    GameState::Solved
+                } else {
+                    GameState::Playing
+                };
+            }
+            SatResult::Unsat => {
+                self.state = GameState::Error;
+                self.conflicts = lines;
+                self.error_info = Some(format!("Conflict: [{}]", cells.join(", ")));
+            }
+            SatResult::Unknown => {
+                self.state = GameState::Error; // Treat unknown as error
+                self.conflicts.clear();
+                self.error_info = Some("Unknown error".to_string());
+            }
+        }
+    }
+
+    // Solved once the filled cells match every clue, treating anything not
+    // marked as empty.
+    fn is_solved(&self) -> bool {
+        let rows = (0..self.puzzle.rows).all(|r| {
+            let line: Vec<bool> = (0..self.puzzle.cols)
+                .map(|c| self.cells[r][c] == CellState::Filled)
+                .collect();
+            runs(&line) == self.puzzle.row_clues[r]
+        });
+        let cols = (0..self.puzzle.cols).all(|c| {
+            let line: Vec<bool> = (0..self.puzzle.rows)
+                .map(|r| self.cells[r][c] == CellState::Filled)
+                .collect();
+            runs(&line) == self.puzzle.col_clues[c]
+        });
+        rows && cols
+    }
+}
+
+// The clue that describes a line of filled cells.
+fn runs(line: &[bool]) -> Vec<usize> {
+    line.split(|filled| !filled)
+        .map(|run| run.len())
+        .filter(|&len| len > 0)
+        .collect()
+}
+
+// --- UI Rendering ---
+
+fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &App) -> io::Result<()> {
+    let puzzle = &app.puzzle;
+
+    // Row clues sit to the left of the grid and column clues stack above it.
+    let row_clue_text: Vec<String> = puzzle
+        .row_clues
+        .iter()
+        .map(|clues| {
+            clues
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    let row_clue_width = row_clue_text.iter().map(|s| s.len()).max().unwrap_or(0);
+    let col_clue_height = puzzle.col_clues.iter().map(|c| c.len()).max().unwrap_or(0);
+
+    let conflict_style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let clue_style = Style::default().fg(Color::Cyan);
+
+    terminal.draw(|f| {
+        // Vertical Layout: Title, Spacer, Puzzle, Spacer, Info
+        let vertical_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),                                          // Title Box
+                Constraint::Min(1),                                             // Top Spacer
+                Constraint::Length((col_clue_height + puzzle.rows + 1) as u16), // Puzzle Grid
+                Constraint::Min(1),                                             // Bottom Spacer
+                Constraint::Length(5),                                          // Info Panel
+            ])
+            .split(f.area());
+
+        // Horizontal Centering for Puzzle
+        let puzzle_width = (row_clue_width + 1 + puzzle.cols * 2) as u16;
+        let horizontal_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(puzzle_width),
+                Constraint::Min(1),
+            ])
+            .split(vertical_chunks[2]);
+        let center_area = horizontal_chunks[1];
+
+        // --- Title ---
+        let title_block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Cyan))
+            .title(" Z3 Tutorial ");
+
+        let title_text = Paragraph::new("Nonogram (Push/Pop)")
+            .block(title_block)
+            .alignment(Alignment::Center)
+            .style(
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        f.render_widget(title_text, vertical_chunks[0]);
+
+        // --- Grid ---
+        let mut grid_text = Vec::new();
+
+        // Column clues, bottom aligned so the last block sits on the grid.
+        for k in 0..col_clue_height {
+            let mut spans = vec![Span::raw(" ".repeat(row_clue_width + 1))];
+            for (c, clues) in puzzle.col_clues.iter().enumerate() {
+                let pad = col_clue_height - clues.len();
+                let text = if k >= pad {
+                    format!("{:>2}", clues[k - pad])
+                } else {
+                    "  ".to_string()
+                };
+                let style = if app.conflicts.contains(&LineId::Col(c)) {
+                    conflict_style
+                } else {
+                    clue_style
+                };
+                spans.push(Span::styled(text, style));
+            }
+            grid_text.push(Line::from(spans));
+        }
+
+        for (r, clue_text) in row_clue_text.iter().enumerate() {
+            let style = if app.conflicts.contains(&LineId::Row(r)) {
+                conflict_style
+            } else {
+                clue_style
+            };
+            let mut row_spans = vec![
+                Span::styled(format!("{:>w$}", clue_text, w = row_clue_width), style),
+                Span::raw(" "),
+            ];
+
+            for c in 0..puzzle.cols {
+                let (text, mut style) = match app.cells[r][c] {
+                    CellState::Filled => ("██", Style::default().fg(Color::Yellow)),
+                    CellState::Empty => (" x", Style::default().fg(Color::DarkGray)),
+                    CellState::Unknown => (" .", Style::default()),
+                };
+                if (r, c) == app.cursor {
+                    style = style.bg(Color::White).fg(Color::Black);
+                }
+                row_spans.push(Span::styled(text, style));
+            }
+            grid_text.push(Line::from(row_spans));
+        }
+
+        let grid_widget = Paragraph::new(grid_text)
+            .alignment(Alignment::Left)
+            .block(Block::default());
+
+        f.render_widget(grid_widget, center_area);
+
+        // --- Info / Status ---
+        let status_text = match app.state {
+            GameState::Playing => {
+                Span::styled("Status: Playing (SAT)", Style::default().fg(Color::Green))
+            }
+            GameState::Error => {
+                let msg = if let Some(ref info) = app.error_info {
+                    format!("Status: UNSAT! {}", info)
+                } else {
+                    "Status: UNSAT! Conflict.".to_string()
+                };
+                Span::styled(msg, conflict_style)
+            }
+            GameState::Solved => Span::styled(
+                "Status: SOLVED!",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        };
+
+        let help_text = vec![
+            Line::from(status_text),
+            Line::from(Span::styled(
+                "ARROWS: Move | SPACE: Cycle | F: Fill | X: Empty | BACKSPACE: Clear | Q: Quit",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let info_block = Paragraph::new(help_text)
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .title(" Info ")
+                    .style(Style::default().fg(Color::Blue)),
+            )
+            .alignment(Alignment::Center);
+
+        f.render_widget(info_block, vertical_chunks[4]);
+    })?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let puzzle = get_input();
+    if puzzle.rows == 0
+        || puzzle.cols == 0
+        || puzzle.row_clues.len() != puzzle.rows
+        || puzzle.col_clues.len() != puzzle.cols
+    {
+        eprintln!("Puzzle clue counts don't match its rows and columns.");
+        std::process::exit(1);
+    }
+
+    // Setup Terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create App
+    let mut app = App::new(puzzle);
+
+    // Main Loop
+    loop {
+        draw_ui(&mut terminal, &app)?;
+
+        if event::poll(Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+            && app.on_key(key.code)
+        {
+            break;
+        }
+    }
+
+    // Restore Terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CellState, LineId, NonogramPuzzle, NonogramSolver};
+    use z3::SatResult;
+
+    // A 2x2 puzzle with one filled cell in every row and column.
+    fn diagonal() -> NonogramSolver {
+        NonogramSolver::new(&NonogramPuzzle {
+            rows: 2,
+            cols: 2,
+            row_clues: vec![vec![1], vec![1]],
+            col_clues: vec![vec![1], vec![1]],
+        })
+    }
+
+    #[test]
+    fn a_fitting_placement_has_no_conflict() {
+        let cells = vec![
+            vec![CellState::Filled, CellState::Unknown],
+            vec![CellState::Unknown, CellState::Unknown],
+        ];
+        let (result, lines, cells) = diagonal().check(&cells);
+        assert_eq!(result, SatResult::Sat);
+        assert!(lines.is_empty() && cells.is_empty());
+    }
+
+    #[test]
+    fn a_broken_line_is_reported_with_its_cells() {
+        // Filling all of row 0 breaks its clue of a single cell.
+        let cells = vec![
+            vec![CellState::Filled, CellState::Filled],
+            vec![CellState::Unknown, CellState::Unknown],
+        ];
+        let (result, lines, cells) = diagonal().check(&cells);
+        assert_eq!(result, SatResult::Unsat);
+        assert!(lines.contains(&LineId::Row(0)), "{:?}", lines);
+        assert!(!lines.contains(&LineId::Row(1)), "{:?}", lines);
+        assert_eq!(cells, vec!["(0,0)=#", "(0,1)=#"]);
+    }
+}
@@ -8,4 +8,7 @@ fn main() {
     println!("  cargo run -p part_05_application --bin restaurant");
     println!("  cargo run -p part_05_application --bin nonogram");
     println!("  cargo run -p part_05_application --bin nanobots");
+    println!();
+    println!("To play a nonogram yourself with Z3 checking your moves:");
+    println!("  cargo run -p part_05_application --bin nonogram_player <puzzle.json>");
 }
\ No newline at end of file
@@ -32,9 +32,9 @@ fn main() {
     if solver.check() == z3::SatResult::Sat {
         let model = solver.get_model().unwrap();
         println!("Solution found:");
-        println!("  Croissants: {}", model.eval(&c, true).unwrap());
-        println!("  Bagels:     {}", model.eval(&b, true).unwrap());
-        println!("  Muffins:    {}", model.eval(&m, true).unwrap());
+        println!("  Croissants: {}", numerals::eval_int(&model, &c));
+        println!("  Bagels:     {}", numerals::eval_int(&model, &b));
+        println!("  Muffins:    {}", numerals::eval_int(&model, &m));
     } else {
         println!("No solution found.");
     }
@@ -1,3 +1,6 @@
+use enumeration::Enumerator;
+use numerals::BigInt;
+use std::time::Duration;
 use z3::ast::{Ast, Int};
 use z3::Solver;
 
@@ -51,9 +54,24 @@ fn main() {
 
     println!("{solver:?}");
 
-    for grid in solver.solutions(&grid, true) {
+    // Every magic square comes in 8 orientations (4 rotations, each of them
+    // mirrored). Enumerate them all, but only report one per orientation
+    // class.
+    let found = Enumerator::new(&solver, &grid)
+        .limit(100)
+        .time_budget(Duration::from_secs(10))
+        .progress_every(1, |progress| {
+            println!(
+                "  ... {} solutions ({} duplicates) after {:.2?}",
+                progress.solutions, progress.duplicates, progress.elapsed
+            )
+        })
+        .canonical(|grid| canonical(grid))
+        .run();
+
+    for grid in &found.solutions {
         println!("Magic Square Found:");
-        for row in &grid {
+        for row in grid {
             for cell in row {
                 print!("{} ", cell);
             }
@@ -61,4 +79,39 @@ fn main() {
         }
         println!();
     }
+    println!(
+        "{} distinct up to rotation and reflection, {} more were orientations of those ({:?} after {:?}).",
+        found.solutions.len(),
+        found.duplicates,
+        found.stop,
+        found.elapsed
+    );
+}
+
+// The smallest of a square's 8 orientations, read row by row.
+fn canonical(grid: &[Vec<Int>]) -> Vec<BigInt> {
+    let cells: Vec<Vec<BigInt>> = grid
+        .iter()
+        .map(|row| row.iter().map(numerals::int).collect())
+        .collect();
+    let n = cells.len();
+
+    let mut orientations = Vec::new();
+    for transpose in [false, true] {
+        for flip_rows in [false, true] {
+            for flip_cols in [false, true] {
+                let mut flat = Vec::with_capacity(n * n);
+                for r in 0..n {
+                    for c in 0..n {
+                        let (r, c) = if transpose { (c, r) } else { (r, c) };
+                        let r = if flip_rows { n - 1 - r } else { r };
+                        let c = if flip_cols { n - 1 - c } else { c };
+                        flat.push(cells[r][c].clone());
+                    }
+                }
+                orientations.push(flat);
+            }
+        }
+    }
+    orientations.into_iter().min().unwrap()
 }
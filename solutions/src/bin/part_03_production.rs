@@ -30,9 +30,9 @@ fn main() {
 
     if opt.check(&[]) == SatResult::Sat {
         let model = opt.get_model().unwrap();
-        let c_val = model.eval(&chairs, true).unwrap();
-        let t_val = model.eval(&tables, true).unwrap();
-        let p_val = model.eval(&profit, true).unwrap();
+        let c_val = numerals::eval_int(&model, &chairs);
+        let t_val = numerals::eval_int(&model, &tables);
+        let p_val = numerals::eval_int(&model, &profit);
 
         println!("Optimal Production Plan:");
         println!("  Chairs: {}", c_val);
@@ -151,7 +151,7 @@ impl ColoringSolver {
             let model = self.solver.get_model().unwrap();
             println!("Region Colors:");
             for (i, r) in map.regions.iter().enumerate() {
-                let c_val = model.eval(r, true).unwrap().as_i64().unwrap();
+                let c_val = numerals::eval_i64(&model, r);
                 let c_name = match c_val {
                     1 => "Red",
                     2 => "Green",
@@ -178,4 +178,3 @@ fn main() {
     // Run Search
     coloring_solver.solve_dfs(&map);
 }
-
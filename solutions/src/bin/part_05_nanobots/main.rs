@@ -0,0 +1,212 @@
+mod report;
+mod view;
+
+use solutions::deadline::Deadline;
+use solutions::nanobots::explain::Explanation;
+use solutions::nanobots::metric::Metric;
+use solutions::nanobots::{
+    octree, optimize, strongest_in_range, Bot, InputData, ModeResult, Output, Solver,
+};
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::time::Instant;
+use z3::SatResult;
+
+// How to search for the best point.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    // One soft constraint per bot in a single Optimize. Fine for a handful
+    // of bots.
+    Optimize,
+    // Octree subdivision with Z3 refining small boxes. Scales to the real
+    // puzzle input.
+    Octree,
+    // Run both and check they agree.
+    Compare,
+}
+
+// Command line options. Anything that isn't a flag is treated as the input path.
+struct Options {
+    path: Option<String>,
+    mode: Mode,
+    metric: Metric,
+    // List up to this many optimal points and describe the region they lie in.
+    enumerate: Option<usize>,
+    // List every bot with how far it is from the point.
+    report: bool,
+    // Print one JSON document instead of the text output.
+    json: bool,
+    // Browse slices of the field around the optimum afterwards.
+    view: bool,
+}
+
+fn get_options() -> Options {
+    let mut options = Options {
+        path: None,
+        mode: Mode::Optimize,
+        metric: Metric::Manhattan,
+        enumerate: None,
+        report: false,
+        json: false,
+        view: false,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--mode" => {
+                options.mode = match args.next().as_deref() {
+                    Some("optimize") => Mode::Optimize,
+                    Some("octree") => Mode::Octree,
+                    Some("compare") => Mode::Compare,
+                    _ => panic!("--mode must be one of: optimize, octree, compare"),
+                }
+            }
+            "--metric" => {
+                options.metric = args.next().as_deref().and_then(Metric::parse).expect(
+                    "--metric must be one of: manhattan, chebyshev, euclidean2, weighted:wx,wy,wz",
+                )
+            }
+            "--all" => {
+                options.enumerate = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("--all needs a limit on the number of points"),
+                )
+            }
+            "--report" => options.report = true,
+            "--json" => options.json = true,
+            "--view" => options.view = true,
+            _ => options.path = Some(arg),
+        }
+    }
+    if options.json && options.enumerate.is_some() {
+        panic!("--all only has a text report, so it can't be combined with --json");
+    }
+    if options.json && options.view {
+        panic!("--view takes over the terminal, so it can't be combined with --json");
+    }
+    options
+}
+
+fn get_input(path: Option<&str>) -> Vec<Bot> {
+    let mut reader: Box<dyn Read> = match path {
+        Some(path) => Box::new(File::open(path).expect("Failed to open file")),
+        None => Box::new(io::stdin()),
+    };
+
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .expect("Failed to read input");
+
+    // JSON from the course inputs, or the raw puzzle text one bot per line.
+    if input.trim_start().starts_with('{') {
+        let data: InputData = serde_json::from_str(&input).expect("Failed to parse JSON input");
+        data.bots
+    } else {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Bot::parse)
+            .collect()
+    }
+}
+
+fn main() {
+    let options = get_options();
+    let bots = get_input(options.path.as_deref());
+    if let Err(problem) = options.metric.check(&bots) {
+        eprintln!("Invalid input: {}", problem);
+        std::process::exit(1);
+    }
+    let strongest = strongest_in_range(&bots, options.metric);
+    if !options.json {
+        println!("Metric: {}", options.metric);
+        println!("In range of the strongest bot: {}", strongest);
+    }
+
+    let modes: &[(&str, Solver)] = match options.mode {
+        Mode::Optimize => &[("optimize", optimize::solve)],
+        Mode::Octree => &[("octree", octree::solve)],
+        Mode::Compare => &[("optimize", optimize::solve), ("octree", octree::solve)],
+    };
+
+    let mut results = Vec::new();
+    for (name, solve) in modes {
+        let start = Instant::now();
+        let solution = solve(&bots, options.metric, Deadline::NONE);
+        let elapsed = start.elapsed();
+        let result = ModeResult::new(
+            &bots,
+            options.metric,
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            &solution,
+        );
+
+        if !options.json {
+            println!("--- Mode: {} ---", name);
+            match (&result.solution, &solution) {
+                (Some(explanation), _) => report::explanation(explanation, options.report),
+                (None, Err(SatResult::Unknown)) => println!("UNKNOWN (Z3 gave up)"),
+                (None, _) => println!("UNSAT"),
+            }
+            println!("Took {:?}", elapsed);
+        }
+        results.push(result);
+    }
+
+    if let (Some(limit), Some(Some(best))) = (
+        options.enumerate,
+        results.first().map(|r| r.solution.as_ref()),
+    ) {
+        report::optimal(&bots, options.metric, best.objective, limit);
+    }
+
+    if options.view {
+        let optimum = results
+            .first()
+            .and_then(|r| r.solution.as_ref())
+            .map(|s| s.point);
+        view::run(&bots, options.metric, optimum).expect("Failed to run the field viewer");
+    }
+
+    let agreed = results
+        .iter()
+        .filter_map(|r| r.solution.as_ref())
+        .all(Explanation::agrees);
+    let answered = results.iter().all(|r| r.status != "unknown");
+
+    // Different modes may land on different points with the same score, so
+    // compare the count and the answer rather than the coordinates.
+    let mut modes_agree = true;
+    if let [a, b] = results.as_slice() {
+        if let (Some(a), Some(b)) = (&a.solution, &b.solution) {
+            modes_agree = (a.objective, a.origin_distance) == (b.objective, b.origin_distance);
+            if !options.json {
+                println!(
+                    "{}",
+                    if modes_agree {
+                        "Modes agree."
+                    } else {
+                        "Modes DISAGREE!"
+                    }
+                );
+            }
+        }
+    }
+
+    if options.json {
+        let output = Output {
+            metric: options.metric.to_string(),
+            strongest_in_range: strongest,
+            results,
+        };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    }
+
+    if !agreed || !modes_agree || !answered {
+        std::process::exit(1);
+    }
+}
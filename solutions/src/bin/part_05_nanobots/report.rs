@@ -0,0 +1,120 @@
+use solutions::nanobots::enumerate;
+use solutions::nanobots::explain::{BotReport, Explanation};
+use solutions::nanobots::metric::Metric;
+use solutions::nanobots::{Bot, Point};
+use std::collections::BTreeMap;
+
+// Prints one mode's answer. With `details`, every bot is listed with how far
+// it is from the point.
+pub fn explanation(explanation: &Explanation, details: bool) {
+    let (x, y, z) = explanation.point;
+    println!("Optimal Coordinate: ({}, {}, {})", x, y, z);
+    println!("Bots in range: {}", explanation.objective);
+    if explanation.agrees() {
+        println!("Recount: {} (agrees)", explanation.recount);
+    } else {
+        println!(
+            "Recount: {} (DISAGREES with the objective)",
+            explanation.recount
+        );
+    }
+    println!("Bots out of range: {}", explanation.out_of_range);
+    println!("Solution: {}", explanation.origin_distance);
+
+    if !details {
+        return;
+    }
+
+    // In range first, then the near misses before the far ones.
+    let mut rows: Vec<&BotReport> = explanation.bots.iter().collect();
+    rows.sort_by_key(|b| (!b.in_range, b.missed_by, b.index));
+    for b in rows {
+        let (bx, by, bz) = b.pos;
+        let status = if b.in_range {
+            "in range".to_string()
+        } else {
+            format!("missed by {}", b.missed_by)
+        };
+        println!(
+            "  #{} pos=<{},{},{}>, r={}: distance {} of {}, {}",
+            b.index, bx, by, bz, b.r, b.distance, b.reach, status
+        );
+    }
+}
+
+// Enumerates the optimal points, prints the bots in range of each, then
+// describes the region each distinct group of bots carves out.
+pub fn optimal(bots: &[Bot], metric: Metric, count: usize, limit: usize) {
+    let points = enumerate::optimal_points(bots, metric, count, limit);
+
+    println!("--- Optimal points ({} bots in range) ---", count);
+    let mut groups: BTreeMap<Vec<usize>, Vec<Point>> = BTreeMap::new();
+    for &point in &points {
+        let reached: Vec<usize> = bots
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| metric.in_range(b, point))
+            .map(|(i, _)| i)
+            .collect();
+        let (x, y, z) = point;
+        println!(
+            "({}, {}, {}) distance {}: bots {}",
+            x,
+            y,
+            z,
+            metric.distance(point, (0, 0, 0)),
+            join(&reached)
+        );
+        groups.entry(reached).or_default().push(point);
+    }
+    if points.len() == limit {
+        println!(
+            "Stopped at the limit of {} points; there may be more.",
+            limit
+        );
+    } else {
+        println!("That is every optimal point: {} in total.", points.len());
+    }
+
+    println!("--- Optimal region ---");
+    for (group, points) in &groups {
+        let in_range: Vec<&Bot> = group.iter().map(|&i| &bots[i]).collect();
+        println!("In range of bots {} ({} found):", join(group), points.len());
+        match enumerate::region(&in_range, metric) {
+            Some(facets) => {
+                for facet in facets {
+                    println!("  {} <= {} <= {}", facet.lo, label(facet.form), facet.hi);
+                }
+            }
+            None => println!("  no linear description for the {} metric", metric),
+        }
+    }
+}
+
+fn join(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Writes a form like (1, -1, 1) as `x-y+z` and (2, 0, 0) as `2x`.
+fn label((a, b, c): Point) -> String {
+    let mut out = String::new();
+    for (coef, var) in [(a, 'x'), (b, 'y'), (c, 'z')] {
+        if coef == 0 {
+            continue;
+        }
+        if coef < 0 {
+            out.push('-');
+        } else if !out.is_empty() {
+            out.push('+');
+        }
+        if coef.abs() != 1 {
+            out.push_str(&coef.abs().to_string());
+        }
+        out.push(var);
+    }
+    out
+}
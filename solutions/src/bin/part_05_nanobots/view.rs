@@ -0,0 +1,278 @@
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Terminal,
+};
+use solutions::nanobots::metric::Metric;
+use solutions::nanobots::{Bot, Point};
+use std::io::{self, Stdout};
+use std::time::Duration;
+
+// Each cell is drawn this many characters wide, enough for a count up to 999.
+const CELL_WIDTH: u16 = 4;
+
+// Zooming out stops at this many units per cell, and panning stops this far
+// from the origin, which is far beyond any puzzle input. Together they keep
+// every coordinate on screen well inside an i64.
+const MAX_SCALE: i64 = 1 << 32;
+const MAX_COORD: i64 = 1 << 40;
+
+// Cold to hot, picked by a cell's share of the busiest visible cell.
+const HEAT: [Color; 6] = [
+    Color::Black,
+    Color::Blue,
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Red,
+];
+
+struct App<'a> {
+    bots: &'a [Bot],
+    metric: Metric,
+    optimum: Option<Point>,
+    // The point at the middle of the screen; its z is the slice plane.
+    center: Point,
+    // How many units along x and y each cell spans.
+    scale: i64,
+}
+
+impl<'a> App<'a> {
+    fn new(bots: &'a [Bot], metric: Metric, optimum: Option<Point>) -> Self {
+        // Start on the optimum, zoomed out far enough to take in the field.
+        let extent = bots
+            .iter()
+            .map(|b| {
+                b.x.saturating_abs()
+                    .max(b.y.saturating_abs())
+                    .saturating_add(b.r)
+            })
+            .max()
+            .unwrap_or(1);
+        let mut scale = 1;
+        while scale * 32 < extent && scale < MAX_SCALE {
+            scale *= 2;
+        }
+
+        Self {
+            bots,
+            metric,
+            optimum,
+            center: optimum.unwrap_or((0, 0, 0)),
+            scale,
+        }
+    }
+
+    // Bots covering the point a cell samples, which is its top-left corner.
+    fn coverage(&self, point: Point) -> usize {
+        self.bots
+            .iter()
+            .filter(|b| self.metric.in_range(b, point))
+            .count()
+    }
+
+    // Whether the optimum falls inside the cell starting at `point`.
+    fn holds_optimum(&self, (x, y, z): Point) -> bool {
+        match self.optimum {
+            Some((ox, oy, oz)) => {
+                oz == z
+                    && (x..x.saturating_add(self.scale)).contains(&ox)
+                    && (y..y.saturating_add(self.scale)).contains(&oy)
+            }
+            None => false,
+        }
+    }
+
+    fn on_key(&mut self, key: KeyCode) -> bool {
+        let (x, y, z) = self.center;
+        let step = self.scale;
+        let clamp = |v: i64| v.clamp(-MAX_COORD, MAX_COORD);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Up | KeyCode::Char('k') => self.center = (x, clamp(y.saturating_sub(step)), z),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.center = (x, clamp(y.saturating_add(step)), z)
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.center = (clamp(x.saturating_sub(step)), y, z)
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.center = (clamp(x.saturating_add(step)), y, z)
+            }
+            // The slice moves one unit at a time so no plane is skipped.
+            KeyCode::PageUp | KeyCode::Char(']') => self.center = (x, y, clamp(z + 1)),
+            KeyCode::PageDown | KeyCode::Char('[') => self.center = (x, y, clamp(z - 1)),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.scale = (self.scale / 2).max(1),
+            KeyCode::Char('-') => self.scale = (self.scale * 2).min(MAX_SCALE),
+            KeyCode::Char('o') => {
+                if let Some(optimum) = self.optimum {
+                    self.center = optimum;
+                    self.scale = 1;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+}
+
+fn draw_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &App) -> io::Result<()> {
+    terminal.draw(|f| {
+        let vertical_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title Box
+                Constraint::Min(1),    // Heatmap
+                Constraint::Length(4), // Info Panel
+            ])
+            .split(f.area());
+
+        // --- Title ---
+        let title_block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Cyan))
+            .title(" Z3 Tutorial ");
+
+        let (cx, cy, cz) = app.center;
+        let title_text = Paragraph::new(format!(
+            "Nanobot Field: z = {}, {} unit(s) per cell",
+            cz, app.scale
+        ))
+        .block(title_block)
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        f.render_widget(title_text, vertical_chunks[0]);
+
+        // --- Heatmap ---
+        let area = vertical_chunks[1];
+        let cols = (area.width / CELL_WIDTH) as i64;
+        let rows = area.height as i64;
+        let left = cx.saturating_sub((cols / 2).saturating_mul(app.scale));
+        let top = cy.saturating_sub((rows / 2).saturating_mul(app.scale));
+
+        let cells: Vec<Vec<(Point, usize)>> = (0..rows)
+            .map(|r| {
+                (0..cols)
+                    .map(|c| {
+                        let point = (
+                            left.saturating_add(c.saturating_mul(app.scale)),
+                            top.saturating_add(r.saturating_mul(app.scale)),
+                            cz,
+                        );
+                        (point, app.coverage(point))
+                    })
+                    .collect()
+            })
+            .collect();
+        let busiest = cells.iter().flatten().map(|&(_, n)| n).max().unwrap_or(0);
+
+        let mut heat_text = Vec::new();
+        for row in &cells {
+            let mut spans = Vec::new();
+            for &(point, count) in row {
+                let width = CELL_WIDTH as usize;
+                let style = if app.holds_optimum(point) {
+                    Style::default()
+                        .bg(Color::Magenta)
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    let level = (count * (HEAT.len() - 1)).div_ceil(busiest.max(1));
+                    Style::default().bg(HEAT[level]).fg(Color::White)
+                };
+                let text = if app.holds_optimum(point) {
+                    format!("{:>width$}", format!("*{}", count))
+                } else if count == 0 {
+                    " ".repeat(width)
+                } else {
+                    format!("{:>width$}", count)
+                };
+                spans.push(Span::styled(text, style));
+            }
+            heat_text.push(Line::from(spans));
+        }
+
+        f.render_widget(Paragraph::new(heat_text), area);
+
+        // --- Info / Status ---
+        let status_text = match app.optimum {
+            Some((ox, oy, oz)) => Span::styled(
+                format!(
+                    "Optimum * at ({}, {}, {}) | Centre ({}, {}) | Busiest cell: {} bots",
+                    ox, oy, oz, cx, cy, busiest
+                ),
+                Style::default().fg(Color::Green),
+            ),
+            None => Span::styled(
+                format!("No optimum found | Centre ({}, {})", cx, cy),
+                Style::default().fg(Color::Red),
+            ),
+        };
+
+        let help_text = vec![
+            Line::from(status_text),
+            Line::from(Span::styled(
+                "ARROWS: Pan | [ ]: Slice z | + -: Zoom | O: Optimum | Q: Quit",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let info_block = Paragraph::new(help_text)
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .title(" Info ")
+                    .style(Style::default().fg(Color::Blue)),
+            )
+            .alignment(Alignment::Center);
+
+        f.render_widget(info_block, vertical_chunks[2]);
+    })?;
+    Ok(())
+}
+
+// Slices the field at a fixed z and shows how many bots cover each cell.
+pub fn run(bots: &[Bot], metric: Metric, optimum: Option<Point>) -> io::Result<()> {
+    // Setup Terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create App
+    let mut app = App::new(bots, metric, optimum);
+
+    // Main Loop
+    loop {
+        draw_ui(&mut terminal, &app)?;
+
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if app.on_key(key.code) {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Restore Terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(())
+}
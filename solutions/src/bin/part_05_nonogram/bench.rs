@@ -0,0 +1,121 @@
+use super::get_input;
+use solutions::deadline::Deadline;
+use solutions::nonogram::encoding::Encoding;
+use solutions::nonogram::prepass::line_prepass;
+use solutions::nonogram::validate::validate;
+use solutions::nonogram::{build_model, runs, NonogramPuzzle, Options};
+use std::fs;
+use std::time::{Duration, Instant};
+use z3::SatResult;
+
+// Sizes of the random puzzles added to every benchmark run.
+const GENERATED_SIZES: [usize; 3] = [30, 40, 50];
+
+// Compares the Int and Automaton encodings. Uses the given puzzle files, or
+// every `inputs/nonogram_*.json` when none are given, plus a few generated
+// large puzzles.
+pub fn run(paths: &[String], prepass: bool) {
+    let mut puzzles: Vec<(String, NonogramPuzzle)> = Vec::new();
+
+    let mut paths = paths.to_vec();
+    if paths.is_empty() {
+        if let Ok(entries) = fs::read_dir("inputs") {
+            paths = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path().display().to_string())
+                .filter(|p| p.contains("nonogram_") && p.ends_with(".json"))
+                .collect();
+            paths.sort();
+        }
+    }
+    for path in paths {
+        let puzzle = get_input(Some(&path));
+        puzzles.push((path, puzzle));
+    }
+    for (seed, &size) in GENERATED_SIZES.iter().enumerate() {
+        puzzles.push((
+            format!("generated {}x{}", size, size),
+            generate(size, seed as u64 + 1),
+        ));
+    }
+
+    println!("Pre-pass: {}", if prepass { "enabled" } else { "disabled" });
+    println!(
+        "{:<36} {:>8} {:>14} {:>14}",
+        "Puzzle", "Size", "Int", "Automaton"
+    );
+
+    for (name, puzzle) in &puzzles {
+        if !validate(puzzle).is_empty() {
+            println!("{:<36} invalid", name);
+            continue;
+        }
+
+        let mut known = vec![vec![None; puzzle.cols]; puzzle.rows];
+        if prepass && line_prepass(puzzle, &mut known, Deadline::NONE).is_err() {
+            println!("{:<36} unsolvable", name);
+            continue;
+        }
+
+        let times: Vec<String> = [Encoding::Int, Encoding::Automaton]
+            .iter()
+            .map(|&encoding| {
+                let options = Options { prepass, encoding };
+                match time_solve(puzzle, &known, &options) {
+                    (SatResult::Sat, elapsed) => format!("{:.1?}", elapsed),
+                    (result, _) => format!("{:?}", result),
+                }
+            })
+            .collect();
+
+        println!(
+            "{:<36} {:>8} {:>14} {:>14}",
+            name,
+            format!("{}x{}", puzzle.rows, puzzle.cols),
+            times[0],
+            times[1]
+        );
+    }
+}
+
+// Builds and checks the model, timing both together.
+fn time_solve(
+    puzzle: &NonogramPuzzle,
+    known: &[Vec<Option<bool>>],
+    options: &Options,
+) -> (SatResult, Duration) {
+    let start = Instant::now();
+    let (solver, _grid) = build_model(puzzle, known, options);
+    let result = solver.check();
+    (result, start.elapsed())
+}
+
+// Makes a random size x size picture and derives its clues, so the puzzle is
+// always solvable. A small xorshift keeps the runs repeatable without pulling
+// in a random number crate.
+fn generate(size: usize, seed: u64) -> NonogramPuzzle {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let picture: Vec<Vec<bool>> = (0..size)
+        .map(|_| (0..size).map(|_| next() % 100 < 55).collect())
+        .collect();
+
+    let clues = |line: Vec<bool>| Some(runs(&line).into_iter().map(Some).collect());
+
+    NonogramPuzzle {
+        rows: size,
+        cols: size,
+        row_clues: (0..size)
+            .map(|r| clues((0..size).map(|c| picture[r][c]).collect()))
+            .collect(),
+        col_clues: (0..size)
+            .map(|c| clues((0..size).map(|r| picture[r][c]).collect()))
+            .collect(),
+    }
+}
@@ -0,0 +1,178 @@
+mod bench;
+mod render;
+
+use solutions::deadline::Deadline;
+use solutions::nonogram::encoding::Encoding;
+use solutions::nonogram::prepass::{line_prepass, Stopped};
+use solutions::nonogram::{self, build_model, exact_clues, runs, validate, NonogramPuzzle};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::process;
+use std::time::Instant;
+
+// Command line options. Anything that isn't a flag is treated as an input path.
+#[derive(Default)]
+struct Options {
+    paths: Vec<String>,
+    // The pre-pass and encoding the model is built with.
+    model: nonogram::Options,
+    bench: bool,
+    // Where to write the rendered puzzle, if anywhere.
+    svg: Option<String>,
+    pbm: Option<String>,
+    // Render the empty puzzle with clues only, without solving it.
+    blank: bool,
+}
+
+fn get_options() -> Options {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-prepass" => options.model.prepass = false,
+            "--bench" => options.bench = true,
+            "--encoding" => {
+                let name = args.next().unwrap_or_default();
+                options.model.encoding =
+                    Encoding::parse(&name).expect("--encoding must be one of: int, automaton");
+            }
+            "--svg" => options.svg = args.next(),
+            "--pbm" => options.pbm = args.next(),
+            "--blank" => options.blank = true,
+            _ => options.paths.push(arg),
+        }
+    }
+    options
+}
+
+fn get_input(path: Option<&str>) -> NonogramPuzzle {
+    let reader: Box<dyn Read> = match path {
+        Some(path) => Box::new(File::open(path).expect("Failed to open file")),
+        None => Box::new(io::stdin()),
+    };
+
+    serde_json::from_reader(reader).expect("Failed to parse JSON input")
+}
+
+fn main() {
+    let options = get_options();
+    if options.bench {
+        bench::run(&options.paths, options.model.prepass);
+        return;
+    }
+
+    let puzzle = get_input(options.paths.first().map(String::as_str));
+
+    // Refuse to build anything for a malformed puzzle.
+    let errors = validate::validate(&puzzle);
+    if !errors.is_empty() {
+        eprintln!("Invalid puzzle:");
+        for error in &errors {
+            eprintln!("  {}", error);
+        }
+        process::exit(1);
+    }
+
+    if options.blank {
+        write_images(&puzzle, None, &options);
+        return;
+    }
+
+    // Known cells: None means we leave the cell for Z3 to figure out.
+    let mut known = vec![vec![None; puzzle.cols]; puzzle.rows];
+    if options.model.prepass {
+        match line_prepass(&puzzle, &mut known, Deadline::NONE) {
+            Ok(()) => {
+                let solved = known.iter().flatten().filter(|c| c.is_some()).count();
+                println!(
+                    "Pre-pass solved {} of {} cells by pure logic.",
+                    solved,
+                    puzzle.rows * puzzle.cols
+                );
+            }
+            Err(Stopped::Contradiction(line)) => {
+                println!("Unsolvable: {} has no valid placement.", line);
+                return;
+            }
+            Err(Stopped::Deadline) => unreachable!("the command line sets no deadline"),
+        }
+    }
+
+    let (solver, grid) = build_model(&puzzle, &known, &options.model);
+
+    println!("{solver:?}");
+
+    let start = Instant::now();
+    let result = solver.check();
+    println!("Z3 check took {:?}", start.elapsed());
+
+    if result == z3::SatResult::Sat {
+        let model = solver.get_model().unwrap();
+        let solution: Vec<Vec<bool>> = grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| model.eval(cell, true).unwrap().as_bool().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        println!("Solution:");
+        for row in &solution {
+            for &val in row {
+                print!("{}", if val { "#" } else { " " });
+            }
+            println!();
+        }
+
+        report_wildcards(&puzzle, &solution);
+        write_images(&puzzle, Some(&solution), &options);
+    } else {
+        println!("Unsolvable.");
+    }
+}
+
+// Prints the clues Z3 chose for every line that had wildcards in it.
+fn report_wildcards(puzzle: &NonogramPuzzle, solution: &[Vec<bool>]) {
+    let mut lines = Vec::new();
+    for (r, clues) in puzzle.row_clues.iter().enumerate() {
+        lines.push((format!("row {}", r), clues, solution[r].clone()));
+    }
+    for (c, clues) in puzzle.col_clues.iter().enumerate() {
+        let line: Vec<bool> = solution.iter().map(|row| row[c]).collect();
+        lines.push((format!("column {}", c), clues, line));
+    }
+
+    let mut header = false;
+    for (name, clues, line) in lines {
+        if exact_clues(clues).is_some() {
+            continue;
+        }
+        if !header {
+            println!("Inferred clues:");
+            header = true;
+        }
+        let given = match clues {
+            Some(clues) => clues
+                .iter()
+                .map(|c| c.map_or("?".to_string(), |n| n.to_string()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "?".to_string(),
+        };
+        println!("  {}: [{}] -> {:?}", name, given, runs(&line));
+    }
+}
+
+// Writes the SVG and PBM renderings that were asked for on the command line.
+fn write_images(puzzle: &NonogramPuzzle, cells: Option<&[Vec<bool>]>, options: &Options) {
+    if let Some(path) = &options.svg {
+        fs::write(path, render::to_svg(puzzle, cells)).expect("Failed to write SVG");
+        println!("Wrote {}", path);
+    }
+    if let Some(path) = &options.pbm {
+        fs::write(path, render::to_pbm(puzzle, cells)).expect("Failed to write PBM");
+        println!("Wrote {}", path);
+    }
+}
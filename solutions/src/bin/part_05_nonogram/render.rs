@@ -0,0 +1,260 @@
+use solutions::nonogram::{LineClues, NonogramPuzzle};
+use std::fmt::Write;
+
+// Both renderers draw the same worksheet: row clues in the left margin,
+// column clues stacked in the top margin, and the grid with a heavier line
+// every five cells. Passing `None` for the cells gives an empty puzzle that
+// can be handed out and solved by hand.
+
+// --- SVG ---
+
+const SVG_CELL: usize = 24;
+const SVG_CLUE: usize = 18;
+
+pub fn to_svg(puzzle: &NonogramPuzzle, cells: Option<&[Vec<bool>]>) -> String {
+    let left = margin(&puzzle.row_clues) * SVG_CLUE + SVG_CLUE / 2;
+    let top = margin(&puzzle.col_clues) * SVG_CLUE + SVG_CLUE / 2;
+    let width = left + puzzle.cols * SVG_CELL + 1;
+    let height = top + puzzle.rows * SVG_CELL + 1;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+    let _ = writeln!(
+        svg,
+        r#"<g font-family="sans-serif" font-size="{}" fill="black">"#,
+        SVG_CLUE * 3 / 4
+    );
+
+    // Row clues, right aligned against the grid.
+    for (r, clues) in puzzle.row_clues.iter().enumerate() {
+        let y = top + r * SVG_CELL + SVG_CELL / 2;
+        for (k, clue) in shown(clues).iter().rev().enumerate() {
+            let x = left - SVG_CLUE / 2 - k * SVG_CLUE;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="end" dominant-baseline="central">{}</text>"#,
+                x, y, clue
+            );
+        }
+    }
+
+    // Column clues, bottom aligned against the grid.
+    for (c, clues) in puzzle.col_clues.iter().enumerate() {
+        let x = left + c * SVG_CELL + SVG_CELL / 2;
+        for (k, clue) in shown(clues).iter().rev().enumerate() {
+            let y = top - SVG_CLUE / 2 - k * SVG_CLUE;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                x, y, clue
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    if let Some(cells) = cells {
+        for (r, row) in cells.iter().enumerate() {
+            for (c, _) in row.iter().enumerate().filter(|(_, &filled)| filled) {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="black"/>"#,
+                    left + c * SVG_CELL,
+                    top + r * SVG_CELL,
+                    s = SVG_CELL
+                );
+            }
+        }
+    }
+
+    // Grid lines
+    for r in 0..=puzzle.rows {
+        let y = top + r * SVG_CELL;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="black" stroke-width="{}"/>"#,
+            left,
+            left + puzzle.cols * SVG_CELL,
+            if r % 5 == 0 || r == puzzle.rows { 2 } else { 1 },
+            y = y
+        );
+    }
+    for c in 0..=puzzle.cols {
+        let x = left + c * SVG_CELL;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="black" stroke-width="{}"/>"#,
+            top,
+            top + puzzle.rows * SVG_CELL,
+            if c % 5 == 0 || c == puzzle.cols { 2 } else { 1 },
+            x = x
+        );
+    }
+
+    let _ = writeln!(svg, "</svg>");
+    svg
+}
+
+// --- PBM ---
+//
+// Plain (P1) PBM needs no image crate, but it also has no text, so the clue
+// numbers are drawn with a tiny 3x5 pixel font.
+
+const PBM_SCALE: usize = 3;
+const DIGIT_W: usize = 3;
+const DIGIT_H: usize = 5;
+const DIGITS: [[u8; DIGIT_H]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+// Shown for wildcard clues.
+const QUESTION: [u8; DIGIT_H] = [0b111, 0b001, 0b011, 0b000, 0b010];
+
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec<bool>>,
+}
+
+impl Bitmap {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![vec![false; width]; height],
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        for row in self.pixels.iter_mut().skip(y).take(h) {
+            for px in row.iter_mut().skip(x).take(w) {
+                *px = true;
+            }
+        }
+    }
+
+    // Draws a clue with its top right corner at (right, y).
+    fn text(&mut self, text: &str, right: usize, y: usize) {
+        let mut x = right + 1 - text_width(text);
+        for ch in text.bytes() {
+            let glyph = match ch {
+                b'0'..=b'9' => &DIGITS[(ch - b'0') as usize],
+                _ => &QUESTION,
+            };
+            for (dy, bits) in glyph.iter().enumerate() {
+                for dx in 0..DIGIT_W {
+                    if bits & (1 << (DIGIT_W - 1 - dx)) != 0 {
+                        self.pixels[y + dy][x + dx] = true;
+                    }
+                }
+            }
+            x += DIGIT_W + 1;
+        }
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    text.len() * (DIGIT_W + 1) - 1
+}
+
+pub fn to_pbm(puzzle: &NonogramPuzzle, cells: Option<&[Vec<bool>]>) -> String {
+    let widest = puzzle
+        .row_clues
+        .iter()
+        .chain(puzzle.col_clues.iter())
+        .flat_map(shown)
+        .map(|text| text_width(&text))
+        .max()
+        .unwrap_or(DIGIT_W);
+
+    // Every clue gets a slot wide enough for the widest number plus padding.
+    let slot_w = widest + 3;
+    let slot_h = DIGIT_H + 3;
+    let cell = slot_w.max(slot_h);
+    let left = margin(&puzzle.row_clues) * slot_w + 2;
+    let top = margin(&puzzle.col_clues) * slot_h + 2;
+
+    let mut bitmap = Bitmap::new(left + puzzle.cols * cell + 1, top + puzzle.rows * cell + 1);
+
+    for (r, clues) in puzzle.row_clues.iter().enumerate() {
+        let y = top + r * cell + (cell - DIGIT_H) / 2;
+        for (k, clue) in shown(clues).iter().rev().enumerate() {
+            bitmap.text(clue, left - 3 - k * slot_w, y);
+        }
+    }
+    for (c, clues) in puzzle.col_clues.iter().enumerate() {
+        let right = left + c * cell + (cell + widest) / 2;
+        for (k, clue) in shown(clues).iter().rev().enumerate() {
+            bitmap.text(clue, right, top - 2 - DIGIT_H - k * slot_h);
+        }
+    }
+
+    if let Some(cells) = cells {
+        for (r, row) in cells.iter().enumerate() {
+            for (c, _) in row.iter().enumerate().filter(|(_, &filled)| filled) {
+                bitmap.fill(left + c * cell, top + r * cell, cell, cell);
+            }
+        }
+    }
+
+    // Grid lines, doubled every five cells.
+    for r in 0..=puzzle.rows {
+        let thick = if r % 5 == 0 || r == puzzle.rows { 2 } else { 1 };
+        let y = (top + r * cell).min(bitmap.height - thick);
+        bitmap.fill(left, y, puzzle.cols * cell + 1, thick);
+    }
+    for c in 0..=puzzle.cols {
+        let thick = if c % 5 == 0 || c == puzzle.cols { 2 } else { 1 };
+        let x = (left + c * cell).min(bitmap.width - thick);
+        bitmap.fill(x, top, thick, puzzle.rows * cell + 1);
+    }
+
+    // Scale up so the worksheet is a sensible size when printed. Plain PBM
+    // lines shouldn't be longer than 70 characters.
+    let width = bitmap.width * PBM_SCALE;
+    let mut pbm = format!("P1\n{} {}\n", width, bitmap.height * PBM_SCALE);
+    for row in &bitmap.pixels {
+        let line: String = row
+            .iter()
+            .flat_map(|&px| std::iter::repeat_n(if px { '1' } else { '0' }, PBM_SCALE))
+            .collect();
+        for _ in 0..PBM_SCALE {
+            for chunk in line.as_bytes().chunks(70) {
+                pbm.push_str(std::str::from_utf8(chunk).unwrap());
+                pbm.push('\n');
+            }
+        }
+    }
+    pbm
+}
+
+// The clue text for a line. Empty lines are marked with a 0 so they don't
+// look like a missing clue, and wildcards are shown as "?".
+fn shown(clues: &LineClues) -> Vec<String> {
+    match clues {
+        None => vec!["?".to_string()],
+        Some(clues) if clues.is_empty() => vec!["0".to_string()],
+        Some(clues) => clues
+            .iter()
+            .map(|c| c.map_or("?".to_string(), |n| n.to_string()))
+            .collect(),
+    }
+}
+
+// The most clues on any one line, which sets the margin size.
+fn margin(lines: &[LineClues]) -> usize {
+    lines.iter().map(|l| shown(l).len()).max().unwrap_or(1)
+}
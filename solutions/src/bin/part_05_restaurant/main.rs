@@ -0,0 +1,291 @@
+mod report;
+
+use solutions::restaurant::objective::Objective;
+use solutions::restaurant::validate::Checked;
+use solutions::restaurant::{self, diagnose, pareto, plan, rank, split, validate};
+use solutions::restaurant::{DietRule, InputData};
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+use z3::SatResult;
+
+// Command line options. Anything that isn't a flag is treated as the input path.
+struct Options {
+    path: Option<String>,
+    // None compares every objective side by side.
+    objective: Option<Objective>,
+    // Overrides the input's `diet_rule`.
+    diet_rule: Option<DietRule>,
+    // Split the party even if the input has no `split` section.
+    split: bool,
+    // Show the cost/happiness trade-offs instead of a single answer.
+    pareto: bool,
+    // Print those trade-offs as CSV.
+    csv: bool,
+    // List this many of the best choices, not just the best.
+    top: Option<usize>,
+}
+
+fn get_options() -> Options {
+    let mut options = Options {
+        path: None,
+        objective: Some(Objective::Sum),
+        diet_rule: None,
+        split: false,
+        pareto: false,
+        csv: false,
+        top: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--objective" => {
+                options.objective = match args.next().as_deref() {
+                    Some("all") => None,
+                    Some(name) => Some(
+                        Objective::parse(name)
+                            .expect("--objective must be one of: sum, maximin, lex, nash, all"),
+                    ),
+                    None => panic!("--objective must be one of: sum, maximin, lex, nash, all"),
+                }
+            }
+            "--diet" => {
+                options.diet_rule = match args.next().as_deref() {
+                    Some("zero") => Some(DietRule::Zero),
+                    Some("exclude") => Some(DietRule::Exclude),
+                    _ => panic!("--diet must be one of: zero, exclude"),
+                }
+            }
+            "--split" => options.split = true,
+            "--top" => {
+                options.top = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("--top needs a number"),
+                )
+            }
+            "--pareto" => options.pareto = true,
+            "--csv" => {
+                options.pareto = true;
+                options.csv = true;
+            }
+            _ => options.path = Some(arg),
+        }
+    }
+    options
+}
+
+fn get_input(path: Option<&str>) -> InputData {
+    let reader: Box<dyn Read> = match path {
+        Some(path) => Box::new(File::open(path).expect("Failed to open file")),
+        None => Box::new(io::stdin()),
+    };
+
+    serde_json::from_reader(reader).expect("Failed to parse JSON input")
+}
+
+// Where each person eats on each night, and a one-line summary of it.
+type Pick = (Vec<Vec<usize>>, String);
+
+// Solves under every objective and shows what each one does to each person.
+fn compare(data: &Checked) {
+    // Where each person eats on each night, under each objective.
+    let names = |pick: &[usize]| -> Vec<String> {
+        pick.iter()
+            .map(|&i| data.restaurants[i].name.clone())
+            .collect()
+    };
+    let picks: Vec<Result<Pick, SatResult>> = Objective::ALL
+        .iter()
+        .map(|&objective| {
+            if data.split.is_some() {
+                let assignment = split::assign(data, objective)?;
+                let summary = data
+                    .people
+                    .iter()
+                    .zip(names(&assignment))
+                    .map(|(person, name)| format!("{} at {}", person.name, name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Ok((assignment.iter().map(|&i| vec![i]).collect(), summary))
+            } else {
+                let schedule = match data.nights {
+                    Some(nights) => plan::schedule(data, nights, objective)?,
+                    None => vec![restaurant::choose(data, objective)?],
+                };
+                let summary = names(&schedule).join(", ");
+                Ok((vec![schedule; data.people.len()], summary))
+            }
+        })
+        .collect();
+
+    let width = data
+        .people
+        .iter()
+        .map(|p| p.name.len())
+        .chain(["Minimum".len()])
+        .max()
+        .unwrap();
+
+    print!("{:width$}", "", width = width);
+    for objective in Objective::ALL {
+        print!(" {:>8}", objective.to_string());
+    }
+    println!();
+
+    // Per-person happiness, summed over the nights
+    let happiness: Vec<Option<Vec<i64>>> = picks
+        .iter()
+        .map(|pick| {
+            let (visits, _) = pick.as_ref().ok()?;
+            Some(
+                data.people
+                    .iter()
+                    .zip(visits)
+                    .map(|(person, visits)| {
+                        visits
+                            .iter()
+                            .map(|&i| person.happiness(&data.restaurants, i))
+                            .sum()
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+    let row = |label: &str, value: &dyn Fn(&[i64]) -> i64| {
+        print!("{:width$}", label, width = width);
+        for column in &happiness {
+            match column {
+                Some(column) => print!(" {:>8}", value(column)),
+                None => print!(" {:>8}", "-"),
+            }
+        }
+        println!();
+    };
+    for (p, person) in data.people.iter().enumerate() {
+        row(&person.name, &|column| column[p]);
+    }
+    row("Total", &|column| column.iter().sum());
+    row("Minimum", &|column| {
+        column.iter().copied().min().unwrap_or(0)
+    });
+
+    println!();
+    for (objective, pick) in Objective::ALL.iter().zip(&picks) {
+        match pick {
+            Ok((_, summary)) => println!("{}: {}", objective, summary),
+            Err(SatResult::Unknown) => println!("{}: unknown (Z3 gave up)", objective),
+            Err(_) => println!("{}: no suitable restaurant", objective),
+        }
+    }
+}
+
+fn main() {
+    let options = get_options();
+    let mut data = get_input(options.path.as_deref());
+    if let Some(diet_rule) = options.diet_rule {
+        data.diet_rule = diet_rule;
+    }
+    if options.split && data.split.is_none() {
+        data.split = Some(split::SplitRules::default());
+    }
+
+    let data = match validate::check(data) {
+        Ok(data) => data,
+        Err(problems) => {
+            eprintln!("Invalid input:");
+            for problem in &problems {
+                eprintln!("  {}", problem);
+            }
+            process::exit(1);
+        }
+    };
+
+    if options.pareto {
+        // `nights` asks for a schedule, which the front doesn't cover.
+        if data.nights.is_some() {
+            eprintln!("--pareto compares single dinners, so it can't be used with `nights`.");
+            process::exit(1);
+        }
+        let front = pareto::front(&data);
+        if options.csv {
+            report::pareto_csv(&data, &front);
+        } else {
+            report::pareto(&data, &front);
+        }
+        return;
+    }
+
+    if let Some(n) = options.top {
+        // The same goes for ranking: only single dinners are ranked.
+        if data.nights.is_some() {
+            eprintln!("--top ranks single dinners, so it can't be used with `nights`.");
+            process::exit(1);
+        }
+        let objectives = match options.objective {
+            Some(objective) => vec![objective],
+            None => Objective::ALL.to_vec(),
+        };
+        for objective in objectives {
+            report::top(&data, objective, n, &rank::top(&data, objective, n));
+        }
+        return;
+    }
+
+    let Some(objective) = options.objective else {
+        compare(&data);
+        return;
+    };
+    println!("Objective: {}", objective);
+
+    if data.split.is_some() {
+        match split::assign(&data, objective) {
+            Ok(assignment) => report::split(&data, &assignment),
+            Err(SatResult::Unknown) => println!("Unknown: Z3 gave up before finding a split."),
+            Err(_) => println!("No way to split the party fits the budget and constraints."),
+        }
+        return;
+    }
+
+    if let Some(nights) = data.nights {
+        match plan::schedule(&data, nights, objective) {
+            Ok(schedule) => report::schedule(&data, &schedule),
+            Err(SatResult::Unknown) => println!("Unknown: Z3 gave up before finding a schedule."),
+            Err(_) => println!("No schedule fits the budgets and constraints."),
+        }
+        return;
+    }
+
+    let chosen = restaurant::choose(&data, objective);
+    if let Ok(chosen_idx) = chosen {
+        let total_happiness: i64 = data
+            .people
+            .iter()
+            .map(|person| person.happiness(&data.restaurants, chosen_idx))
+            .sum();
+
+        println!(
+            "Selected Restaurant: {} (Index {})",
+            data.restaurants[chosen_idx].name, chosen_idx
+        );
+        println!("Total Happiness: {}", total_happiness);
+        println!("Cost per head: ${}", data.restaurants[chosen_idx].cost);
+
+        println!("\nIndividual Happiness:");
+        for person in &data.people {
+            let happiness = person.happiness(&data.restaurants, chosen_idx);
+            if person.can_eat_at(&data.restaurants[chosen_idx]) {
+                println!("  {}: {}", person.name, happiness);
+            } else {
+                println!("  {}: {} (can't eat here)", person.name, happiness);
+            }
+        }
+    } else if chosen == Err(SatResult::Unknown) {
+        // Z3 couldn't decide, so there's nothing to diagnose.
+        println!("Unknown: Z3 gave up before choosing a restaurant.");
+    } else {
+        println!("No suitable restaurant found within budget/constraints.");
+        report::diagnosis(&data, &diagnose::explain(&data));
+    }
+}
@@ -0,0 +1,260 @@
+use solutions::restaurant::diagnose::{Diagnosis, Verdict};
+use solutions::restaurant::objective::Objective;
+use solutions::restaurant::pareto::{Front, Point};
+use solutions::restaurant::plan;
+use solutions::restaurant::rank::Ranking;
+use solutions::restaurant::validate::Checked;
+use std::collections::BTreeMap;
+use z3::SatResult;
+
+// Where everyone eats, e.g. "Pizza Palace" or "Sushi Bar (3), Taco Stand (2)".
+pub fn describe(data: &Checked, assignment: &[usize]) -> String {
+    let mut groups: BTreeMap<usize, usize> = BTreeMap::new();
+    for &i in assignment {
+        *groups.entry(i).or_default() += 1;
+    }
+    if groups.len() == 1 {
+        let &i = groups.keys().next().unwrap();
+        return data.restaurants[i].name.clone();
+    }
+    groups
+        .iter()
+        .map(|(&i, count)| format!("{} ({})", data.restaurants[i].name, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Prints who goes where, one restaurant at a time.
+pub fn split(data: &Checked, assignment: &[usize]) {
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (p, &i) in assignment.iter().enumerate() {
+        groups.entry(i).or_default().push(p);
+    }
+
+    println!("Split across {} restaurants:", groups.len());
+    let mut total_cost = 0;
+    let mut total_happiness = 0;
+    for (&i, diners) in &groups {
+        let restaurant = &data.restaurants[i];
+        let cost = restaurant.cost * diners.len() as i64;
+        total_cost += cost;
+        let happiness: Vec<String> = diners
+            .iter()
+            .map(|&p| {
+                let person = &data.people[p];
+                let happiness = person.happiness(&data.restaurants, i);
+                total_happiness += happiness;
+                format!("{} {}", person.name, happiness)
+            })
+            .collect();
+        println!(
+            "  {} ({} diners, ${}): {}",
+            restaurant.name,
+            diners.len(),
+            cost,
+            happiness.join(", ")
+        );
+    }
+    println!("Total Cost: ${} of ${}", total_cost, data.budget);
+    println!("Total Happiness: {}", total_happiness);
+}
+
+// Prints the schedule night by night.
+pub fn schedule(data: &Checked, schedule: &[usize]) {
+    let people = data.people.len() as i64;
+
+    println!("Schedule for {} nights:", schedule.len());
+    let mut total_happiness = 0;
+    for (n, &i) in schedule.iter().enumerate() {
+        let restaurant = &data.restaurants[i];
+        let budget = data.night_budgets.get(n).copied().unwrap_or(data.budget);
+        let happiness: Vec<String> = data
+            .people
+            .iter()
+            .map(|person| {
+                let happiness = person.happiness(&data.restaurants, i);
+                total_happiness += happiness;
+                format!("{} {}", person.name, happiness)
+            })
+            .collect();
+        println!(
+            "  Night {}: {} (${} of ${}) - {}",
+            n + 1,
+            restaurant.name,
+            restaurant.cost * people,
+            budget,
+            happiness.join(", ")
+        );
+    }
+    println!("Total Happiness: {}", total_happiness);
+
+    println!(
+        "\nWorst choices (at most {} each):",
+        data.max_worst_nights.unwrap_or(1)
+    );
+    for (person, worst) in data.people.iter().zip(plan::worst_choices(data)) {
+        let count = match worst {
+            Some(worst) => schedule
+                .iter()
+                .filter(|&&i| person.happiness(&data.restaurants, i) == worst)
+                .count(),
+            None => 0,
+        };
+        println!("  {}: {}", person.name, count);
+    }
+}
+
+// Happiness gained over the next cheaper point, and per extra dollar.
+fn gains(points: &[Point]) -> Vec<Option<(i64, f64)>> {
+    let mut gains = vec![None];
+    for pair in points.windows(2) {
+        let extra_happiness = pair[1].happiness - pair[0].happiness;
+        let extra_cost = pair[1].cost - pair[0].cost;
+        gains.push(Some((
+            extra_happiness,
+            extra_happiness as f64 / extra_cost as f64,
+        )));
+    }
+    gains
+}
+
+// Prints the front as a table, cheapest first.
+pub fn pareto(data: &Checked, front: &Front) {
+    let points = &front.points;
+    if points.is_empty() {
+        if front.partial {
+            println!("Unknown: Z3 gave up before finding any plan.");
+        } else {
+            println!("No plan satisfies the constraints at any cost.");
+        }
+        return;
+    }
+
+    println!(
+        "Cost/happiness trade-offs (* within the ${} budget):",
+        data.budget
+    );
+    println!(
+        "  {:>8} {:>9} {:>6} {:>8}  Plan",
+        "Cost", "Happiness", "+Happy", "+Happy/$"
+    );
+    for (point, gain) in points.iter().zip(gains(points)) {
+        let marker = if point.cost <= data.budget { "*" } else { " " };
+        let (extra, per_dollar) = match gain {
+            Some((extra, per_dollar)) => (format!("+{}", extra), format!("{:.3}", per_dollar)),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        println!(
+            "{} {:>8} {:>9} {:>6} {:>8}  {}",
+            marker,
+            format!("${}", point.cost),
+            point.happiness,
+            extra,
+            per_dollar,
+            describe(data, &point.assignment)
+        );
+    }
+    if front.partial {
+        println!("Z3 gave up before reaching the cheapest plans, so the front is partial.");
+    }
+}
+
+// The same front as CSV, for spreadsheets. A partial front is only flagged
+// on stderr, so the CSV itself stays clean.
+pub fn pareto_csv(data: &Checked, front: &Front) {
+    let points = &front.points;
+    println!("cost,happiness,extra_happiness,happiness_per_dollar,within_budget,plan");
+    for (point, gain) in points.iter().zip(gains(points)) {
+        let (extra, per_dollar) = match gain {
+            Some((extra, per_dollar)) => (extra.to_string(), format!("{:.4}", per_dollar)),
+            None => (String::new(), String::new()),
+        };
+        println!(
+            "{},{},{},{},{},\"{}\"",
+            point.cost,
+            point.happiness,
+            extra,
+            per_dollar,
+            point.cost <= data.budget,
+            describe(data, &point.assignment).replace('"', "\"\"")
+        );
+    }
+    if front.partial {
+        eprintln!("Z3 gave up before reaching the cheapest plans, so the front is partial.");
+    }
+}
+
+// Prints the picks in order. Picks that score the same share a rank and are
+// marked as ties, since the solver's order between them means nothing.
+pub fn top(data: &Checked, objective: Objective, n: usize, ranking: &Ranking) {
+    let picks = &ranking.picks;
+    if picks.is_empty() {
+        if ranking.unknown {
+            println!("Unknown: Z3 gave up before finding any choice.");
+        } else {
+            println!("No suitable restaurant found within budget/constraints.");
+        }
+        return;
+    }
+
+    if picks.len() > n {
+        println!(
+            "Top {} under {}, with ties ({} listed):",
+            n,
+            objective,
+            picks.len()
+        );
+    } else {
+        println!("Top {} under {}:", picks.len(), objective);
+    }
+    let mut rank = 0;
+    for (k, pick) in picks.iter().enumerate() {
+        if k == 0 || pick.score != picks[k - 1].score {
+            rank = k + 1;
+        }
+        let tied = picks
+            .iter()
+            .enumerate()
+            .any(|(j, other)| j != k && other.score == pick.score);
+        println!(
+            "  {:>2}.{} {} - happiness {}, cost ${}",
+            rank,
+            if tied { " (tie)" } else { "" },
+            describe(data, &pick.assignment),
+            pick.happiness,
+            pick.cost
+        );
+    }
+    if ranking.unknown {
+        println!("  Z3 gave up looking for more, so the list may be incomplete.");
+    }
+}
+
+// Prints why each restaurant is ruled out and what budget would fix it.
+pub fn diagnosis(data: &Checked, diagnosis: &Diagnosis) {
+    println!("\nWhy each restaurant is ruled out:");
+    for (restaurant, verdict) in data.restaurants.iter().zip(&diagnosis.verdicts) {
+        match verdict {
+            Verdict::RuledOut(reasons) => {
+                println!("  {}: {}", restaurant.name, reasons.join("; "))
+            }
+            Verdict::Fits => println!("  {}: fits", restaurant.name),
+            Verdict::Unknown => println!("  {}: unknown (Z3 gave up)", restaurant.name),
+        }
+    }
+
+    match diagnosis.raise {
+        Ok((extra, i)) => println!(
+            "\nRaising the budget by ${} to ${} would let {} fit.",
+            extra,
+            data.budget + extra,
+            data.restaurants[i].name
+        ),
+        Err(SatResult::Unknown) => {
+            println!("\nZ3 gave up working out how much more budget would help.")
+        }
+        Err(_) => {
+            println!("\nNo budget increase helps: the dietary rules rule out every restaurant.")
+        }
+    }
+}
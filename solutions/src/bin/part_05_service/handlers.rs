@@ -0,0 +1,217 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use solutions::deadline::Deadline;
+use solutions::nanobots::metric::Metric;
+use solutions::nanobots::{self, ModeResult, Output};
+use solutions::nonogram::encoding::Encoding;
+use solutions::nonogram::prepass::{line_prepass, Stopped};
+use solutions::nonogram::{self, NonogramPuzzle};
+use solutions::restaurant::objective::Objective;
+use solutions::restaurant::{self, plan, split, validate};
+use std::collections::HashMap;
+use std::time::Instant;
+use z3::SatResult;
+
+// An endpoint: the request body and query in, the JSON answer out. Err holds
+// everything wrong with the request. Searches that loop stop at the deadline;
+// single Z3 calls are interrupted by the server instead.
+pub type Handler = fn(&str, &HashMap<String, String>, Deadline) -> Result<Value, Vec<String>>;
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, Vec<String>> {
+    serde_json::from_str(body).map_err(|e| vec![format!("invalid JSON: {}", e)])
+}
+
+// Where one person eats at one dinner.
+#[derive(Serialize)]
+struct Seat {
+    person: String,
+    restaurant: String,
+    happiness: i64,
+}
+
+#[derive(Serialize)]
+struct Dinner {
+    cost: i64,
+    happiness: i64,
+    seats: Vec<Seat>,
+}
+
+// One dinner, or one per night, whichever the input asks for. Every dinner
+// is read back as the restaurant each person eats at.
+pub fn restaurant(
+    body: &str,
+    query: &HashMap<String, String>,
+    deadline: Deadline,
+) -> Result<Value, Vec<String>> {
+    let data: restaurant::InputData = parse(body)?;
+    let objective = match query.get("objective") {
+        Some(name) => Objective::parse(name)
+            .ok_or_else(|| vec!["objective must be one of: sum, maximin, lex, nash".to_string()])?,
+        None => Objective::Sum,
+    };
+    let data = validate::check(data)?;
+
+    let party = data.people.len();
+    let dinners = if data.split.is_some() {
+        split::assign(&data, objective).map(|assignment| vec![assignment])
+    } else if let Some(nights) = data.nights {
+        plan::schedule(&data, nights, objective)
+            .map(|schedule| schedule.into_iter().map(|i| vec![i; party]).collect())
+    } else {
+        restaurant::choose(&data, objective).map(|i| vec![vec![i; party]])
+    };
+
+    let dinners = match dinners {
+        Ok(dinners) => dinners,
+        Err(SatResult::Unknown) => {
+            // The restaurant models are single Z3 calls, so running out of
+            // time shows up here as an interrupted check.
+            let reason = if deadline.passed() {
+                "interrupted at the deadline"
+            } else {
+                "Z3 gave up"
+            };
+            return Ok(json!({
+                "objective": objective.to_string(),
+                "status": "unknown",
+                "reason": reason,
+            }));
+        }
+        Err(_) => return Ok(json!({ "objective": objective.to_string(), "status": "unsat" })),
+    };
+    let dinners: Vec<Dinner> = dinners
+        .iter()
+        .map(|assignment| {
+            let seats: Vec<Seat> = data
+                .people
+                .iter()
+                .zip(assignment)
+                .map(|(person, &i)| Seat {
+                    person: person.name.clone(),
+                    restaurant: data.restaurants[i].name.clone(),
+                    happiness: person.happiness(&data.restaurants, i),
+                })
+                .collect();
+            Dinner {
+                cost: assignment.iter().map(|&i| data.restaurants[i].cost).sum(),
+                happiness: seats.iter().map(|seat| seat.happiness).sum(),
+                seats,
+            }
+        })
+        .collect();
+    Ok(json!({ "objective": objective.to_string(), "status": "sat", "dinners": dinners }))
+}
+
+// The solved grid, one row of cells per line with true for filled.
+pub fn nonogram(
+    body: &str,
+    query: &HashMap<String, String>,
+    deadline: Deadline,
+) -> Result<Value, Vec<String>> {
+    let puzzle: NonogramPuzzle = parse(body)?;
+    let mut options = nonogram::Options::default();
+    if let Some(name) = query.get("encoding") {
+        options.encoding = Encoding::parse(name)
+            .ok_or_else(|| vec!["encoding must be one of: int, automaton".to_string()])?;
+    }
+    if let Some(prepass) = query.get("prepass") {
+        options.prepass = prepass
+            .parse()
+            .map_err(|_| vec!["prepass must be true or false".to_string()])?;
+    }
+
+    let errors = nonogram::validate::validate(&puzzle);
+    if !errors.is_empty() {
+        return Err(errors.iter().map(|e| e.to_string()).collect());
+    }
+
+    let mut known = vec![vec![None; puzzle.cols]; puzzle.rows];
+    if options.prepass {
+        match line_prepass(&puzzle, &mut known, deadline) {
+            Ok(()) => {}
+            Err(Stopped::Contradiction(line)) => {
+                return Ok(json!({
+                    "status": "unsat",
+                    "reason": format!("{} has no valid placement", line),
+                }))
+            }
+            Err(Stopped::Deadline) => {
+                return Ok(json!({
+                    "status": "unknown",
+                    "reason": "the deadline passed during the line pre-pass",
+                }))
+            }
+        }
+    }
+
+    let (solver, grid) = nonogram::build_model(&puzzle, &known, &options);
+    match solver.check() {
+        SatResult::Sat => {
+            let model = solver.get_model().unwrap();
+            let solution: Vec<Vec<bool>> = grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| model.eval(cell, true).unwrap().as_bool().unwrap())
+                        .collect()
+                })
+                .collect();
+            Ok(json!({ "status": "sat", "solution": solution }))
+        }
+        SatResult::Unsat => Ok(json!({ "status": "unsat" })),
+        SatResult::Unknown => Ok(json!({
+            "status": "unknown",
+            "reason": solver.get_reason_unknown(),
+        })),
+    }
+}
+
+// The same document `part_05_nanobots --json` prints.
+pub fn nanobots(
+    body: &str,
+    query: &HashMap<String, String>,
+    deadline: Deadline,
+) -> Result<Value, Vec<String>> {
+    let data: nanobots::InputData = parse(body)?;
+    let metric = match query.get("metric") {
+        Some(name) => Metric::parse(name).ok_or_else(|| {
+            vec![
+                "metric must be one of: manhattan, chebyshev, euclidean2, weighted:wx,wy,wz"
+                    .to_string(),
+            ]
+        })?,
+        None => Metric::Manhattan,
+    };
+    metric.check(&data.bots).map_err(|problem| vec![problem])?;
+
+    let modes: &[(&str, nanobots::Solver)] = match query.get("mode").map(String::as_str) {
+        None | Some("optimize") => &[("optimize", nanobots::optimize::solve)],
+        Some("octree") => &[("octree", nanobots::octree::solve)],
+        Some("compare") => &[
+            ("optimize", nanobots::optimize::solve),
+            ("octree", nanobots::octree::solve),
+        ],
+        Some(_) => {
+            return Err(vec![
+                "mode must be one of: optimize, octree, compare".to_string()
+            ])
+        }
+    };
+
+    let results = modes
+        .iter()
+        .map(|(name, solve)| {
+            let start = Instant::now();
+            let solution = solve(&data.bots, metric, deadline);
+            let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+            ModeResult::new(&data.bots, metric, name, elapsed_ms, &solution)
+        })
+        .collect();
+    let output = Output {
+        metric: metric.to_string(),
+        strongest_in_range: nanobots::strongest_in_range(&data.bots, metric),
+        results,
+    };
+    Ok(serde_json::to_value(output).unwrap())
+}
@@ -0,0 +1,248 @@
+// A small local HTTP service in front of the Part 5 solvers. Each endpoint
+// takes the same JSON as the matching files in `inputs/`:
+//
+//   POST /restaurant  ?objective=sum|maximin|lex|nash
+//   POST /nonogram    ?encoding=int|automaton&prepass=false
+//   POST /nanobots    ?mode=optimize|octree|compare&metric=manhattan|...
+//
+// Any endpoint also takes `timeout_ms`, which can only shorten the server's
+// own limit. At most `--workers` solves run at once, and at most
+// `--connections` connections are open; past either, requests get a 503. A
+// client has `IO_TIMEOUT` for each read or write before it is dropped.
+
+mod handlers;
+mod http;
+
+use enumeration::interrupt_after;
+use handlers::Handler;
+use http::{read_request, write_response};
+use serde_json::json;
+use solutions::deadline::Deadline;
+use std::collections::HashMap;
+use std::env;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use z3::Context;
+
+// How long a connection may sit idle while we read the request or write the
+// response.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Command line options.
+struct Options {
+    port: u16,
+    timeout_ms: u64,
+    workers: usize,
+    connections: usize,
+}
+
+fn get_options() -> Options {
+    let mut options = Options {
+        port: 8080,
+        timeout_ms: 10_000,
+        workers: thread::available_parallelism().map_or(1, |n| n.get()),
+        connections: 64,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                options.port = args
+                    .next()
+                    .and_then(|p| p.parse().ok())
+                    .expect("--port needs a port number")
+            }
+            "--timeout-ms" => {
+                options.timeout_ms = args
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .expect("--timeout-ms needs a number of milliseconds")
+            }
+            "--workers" => {
+                options.workers = args
+                    .next()
+                    .and_then(|w| w.parse().ok())
+                    .filter(|&w| w > 0)
+                    .expect("--workers needs a positive number")
+            }
+            "--connections" => {
+                options.connections = args
+                    .next()
+                    .and_then(|c| c.parse().ok())
+                    .filter(|&c| c > 0)
+                    .expect("--connections needs a positive number")
+            }
+            _ => panic!("Unknown argument: {}", arg),
+        }
+    }
+    options
+}
+
+fn route(path: &str) -> Option<Handler> {
+    match path {
+        "/restaurant" => Some(handlers::restaurant),
+        "/nonogram" => Some(handlers::nonogram),
+        "/nanobots" => Some(handlers::nanobots),
+        _ => None,
+    }
+}
+
+// Threads of one kind still running: connection threads, or solver threads
+// including ones whose request has already timed out. Capping them means
+// neither idle clients nor slow requests can pile up threads.
+struct Pool {
+    running: AtomicUsize,
+    limit: usize,
+}
+
+// A thread's place in a `Pool`, given back when the thread ends.
+struct Slot(Arc<Pool>);
+
+impl Pool {
+    fn new(limit: usize) -> Arc<Self> {
+        Arc::new(Pool {
+            running: AtomicUsize::new(0),
+            limit,
+        })
+    }
+
+    fn claim(self: &Arc<Self>) -> Option<Slot> {
+        self.running
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.limit).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Slot(Arc::clone(self)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Runs a handler on its own thread, which has its own Z3 context, so
+// concurrent requests never share solver state. Gives up after `timeout_ms`,
+// and so does the handler: it stops at the deadline, and whatever Z3 is still
+// working on is interrupted.
+fn solve(
+    handler: Handler,
+    body: String,
+    query: HashMap<String, String>,
+    timeout_ms: u64,
+    slot: Slot,
+) -> (u16, serde_json::Value) {
+    let timeout = Duration::from_millis(timeout_ms);
+    let deadline = Deadline::after(timeout);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let ctx = Context::thread_local();
+        let result = interrupt_after(&ctx.handle(), Some(timeout), || {
+            handler(&body, &query, deadline)
+        });
+        // Free the slot before answering, so the client can go again at once.
+        drop(slot);
+        // The request may have timed out and stopped listening.
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(Ok(result)) => (200, result),
+        Ok(Err(errors)) => (400, json!({ "errors": errors })),
+        Err(mpsc::RecvTimeoutError::Timeout) => (
+            504,
+            json!({ "errors": [format!("no answer within {} ms", timeout_ms)] }),
+        ),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            (500, json!({ "errors": ["the solver crashed"] }))
+        }
+    }
+}
+
+fn handle(stream: TcpStream, timeout_ms: u64, workers: &Arc<Pool>) {
+    let (status, body) = match read_request(&stream) {
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            (408, json!({ "errors": ["timed out reading the request"] }))
+        }
+        Err(e) => (400, json!({ "errors": [e.to_string()] })),
+        Ok(request) => match route(&request.path) {
+            None => (
+                404,
+                json!({ "errors": [format!("no endpoint at {}", request.path)] }),
+            ),
+            Some(_) if request.method != "POST" => {
+                (405, json!({ "errors": ["only POST is supported"] }))
+            }
+            Some(handler) => {
+                let timeout_ms = match request.query.get("timeout_ms") {
+                    Some(t) => match t.parse::<u64>() {
+                        Ok(t) => t.min(timeout_ms),
+                        Err(_) => {
+                            let errors = json!({ "errors": ["timeout_ms must be a number"] });
+                            let _ = write_response(&stream, 400, &errors.to_string());
+                            return;
+                        }
+                    },
+                    None => timeout_ms,
+                };
+                let Some(slot) = workers.claim() else {
+                    let errors = json!({ "errors": ["all workers are busy, try again later"] });
+                    let _ = write_response(&stream, 503, &errors.to_string());
+                    return;
+                };
+                println!("POST {} (timeout {} ms)", request.path, timeout_ms);
+                solve(handler, request.body, request.query, timeout_ms, slot)
+            }
+        },
+    };
+
+    if let Err(e) = write_response(&stream, status, &body.to_string()) {
+        eprintln!("Failed to write response: {}", e);
+    }
+}
+
+fn main() {
+    let options = get_options();
+    let address = ("127.0.0.1", options.port);
+    let listener = TcpListener::bind(address).expect("Failed to bind the port");
+    println!(
+        "Listening on http://127.0.0.1:{} (timeout {} ms, {} workers, {} connections)",
+        options.port, options.timeout_ms, options.workers, options.connections
+    );
+
+    let workers = Pool::new(options.workers);
+    let connections = Pool::new(options.connections);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // Without these a client that never sends anything would hold
+                // its thread forever.
+                let timeouts = stream
+                    .set_read_timeout(Some(IO_TIMEOUT))
+                    .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)));
+                if let Err(e) = timeouts {
+                    eprintln!("Failed to set up a connection: {}", e);
+                    continue;
+                }
+                let Some(slot) = connections.claim() else {
+                    let errors = json!({ "errors": ["too many connections, try again later"] });
+                    let _ = write_response(&stream, 503, &errors.to_string());
+                    continue;
+                };
+                let timeout_ms = options.timeout_ms;
+                let workers = Arc::clone(&workers);
+                thread::spawn(move || {
+                    handle(stream, timeout_ms, &workers);
+                    drop(slot);
+                });
+            }
+            Err(e) => eprintln!("Failed to accept a connection: {}", e),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::time::{Duration, Instant};
+
+// When the caller stops waiting for an answer. Searches that make many Z3
+// calls, or do a lot of work of their own, check it between steps and give
+// up once it has passed. `Deadline::NONE` never passes.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    pub const NONE: Deadline = Deadline(None);
+
+    pub fn after(timeout: Duration) -> Self {
+        Self(Some(Instant::now() + timeout))
+    }
+
+    pub fn passed(&self) -> bool {
+        self.0.is_some_and(|at| Instant::now() >= at)
+    }
+}
@@ -0,0 +1,6 @@
+// The solvers behind the Part 5 binaries. Each binary is a command line front
+// end over one of these modules, and `part_05_service` serves all three.
+pub mod deadline;
+pub mod nanobots;
+pub mod nonogram;
+pub mod restaurant;
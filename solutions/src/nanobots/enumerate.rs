@@ -0,0 +1,96 @@
+use super::{Bot, Metric, Point};
+
+use z3::ast::{Bool, Int};
+use z3::{SatResult, Solver};
+
+// Every point in range of at least `count` bots, up to `limit` of them. Once
+// `count` is the optimum these are exactly the optimal points, regardless of
+// their distance to the origin.
+pub fn optimal_points(bots: &[Bot], metric: Metric, count: usize, limit: usize) -> Vec<Point> {
+    if bots.is_empty() {
+        return Vec::new();
+    }
+
+    let solver = Solver::new();
+
+    // Target Coordinates
+    let tx = Int::new_const("tx");
+    let ty = Int::new_const("ty");
+    let tz = Int::new_const("tz");
+    let target = (&tx, &ty, &tz);
+
+    // A point in range of any bot is within `r` of it on every axis, so this
+    // box holds every candidate. It also keeps the nonlinear metric from
+    // searching forever.
+    let mut lo = (i64::MAX, i64::MAX, i64::MAX);
+    let mut hi = (i64::MIN, i64::MIN, i64::MIN);
+    for b in bots {
+        lo = (
+            lo.0.min(b.x - b.r),
+            lo.1.min(b.y - b.r),
+            lo.2.min(b.z - b.r),
+        );
+        hi = (
+            hi.0.max(b.x + b.r),
+            hi.1.max(b.y + b.r),
+            hi.2.max(b.z + b.r),
+        );
+    }
+    for (t, lo, hi) in [(&tx, lo.0, hi.0), (&ty, lo.1, hi.1), (&tz, lo.2, hi.2)] {
+        solver.assert(t.ge(lo));
+        solver.assert(t.le(hi));
+    }
+
+    // Hard constraint: the optimum count of bots in range.
+    let in_range: Vec<Bool> = bots
+        .iter()
+        .map(|b| {
+            metric
+                .encode(target, (b.x, b.y, b.z))
+                .le(Int::from_i64(metric.reach(b.r)))
+        })
+        .collect();
+    let counted: Vec<(&Bool, i32)> = in_range.iter().map(|b| (b, 1)).collect();
+    solver.assert(Bool::pb_ge(&counted, count as i32));
+
+    let mut points = Vec::new();
+    while points.len() < limit && solver.check() == SatResult::Sat {
+        let model = solver.get_model().unwrap();
+        let x = numerals::eval_i64(&model, &tx);
+        let y = numerals::eval_i64(&model, &ty);
+        let z = numerals::eval_i64(&model, &tz);
+        points.push((x, y, z));
+
+        // Block this point so the next check has to find a different one.
+        solver.assert(Bool::or(&[tx.eq(x).not(), ty.eq(y).not(), tz.eq(z).not()]));
+    }
+    points
+}
+
+// The bounds one group of bots puts on a linear form of the target: every
+// point in range of all of them has `lo <= form · point <= hi`.
+pub struct Facet {
+    pub form: Point,
+    pub lo: i64,
+    pub hi: i64,
+}
+
+// The integer points in range of every one of `bots`, described as the
+// intersection of their ranges. Each range is the set where every one of the
+// metric's forms stays within `r` of the bot, so intersecting them just
+// tightens the bounds. None for metrics whose ranges aren't polyhedra.
+pub fn region(bots: &[&Bot], metric: Metric) -> Option<Vec<Facet>> {
+    let facets = metric
+        .forms()?
+        .into_iter()
+        .map(|form| {
+            let dot = |b: &Bot| form.0 * b.x + form.1 * b.y + form.2 * b.z;
+            Facet {
+                form,
+                lo: bots.iter().map(|b| dot(b) - b.r).max().unwrap_or(i64::MIN),
+                hi: bots.iter().map(|b| dot(b) + b.r).min().unwrap_or(i64::MAX),
+            }
+        })
+        .collect();
+    Some(facets)
+}
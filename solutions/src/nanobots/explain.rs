@@ -0,0 +1,67 @@
+use super::{Bot, Metric, Point, Solution};
+use serde::Serialize;
+
+// How one bot relates to the chosen point. Distances are in the metric's own
+// units, so squared for squared Euclidean.
+#[derive(Serialize)]
+pub struct BotReport {
+    pub index: usize,
+    pub pos: Point,
+    pub r: i64,
+    pub distance: i64,
+    pub reach: i64,
+    pub in_range: bool,
+    // How much further the bot would have to reach; 0 when it's in range.
+    pub missed_by: i64,
+}
+
+// Everything we know about one solution: the search's own count next to a
+// recount done bot by bot in plain Rust.
+#[derive(Serialize)]
+pub struct Explanation {
+    pub point: Point,
+    pub origin_distance: i64,
+    pub objective: usize,
+    pub recount: usize,
+    pub out_of_range: usize,
+    pub bots: Vec<BotReport>,
+}
+
+impl Explanation {
+    pub fn new(bots: &[Bot], metric: Metric, solution: &Solution) -> Self {
+        let bots: Vec<BotReport> = bots
+            .iter()
+            .enumerate()
+            .map(|(index, b)| {
+                let pos = (b.x, b.y, b.z);
+                let distance = metric.distance(pos, solution.point);
+                let reach = metric.reach(b.r);
+                BotReport {
+                    index,
+                    pos,
+                    r: b.r,
+                    distance,
+                    reach,
+                    in_range: distance <= reach,
+                    missed_by: (distance - reach).max(0),
+                }
+            })
+            .collect();
+        let recount = bots.iter().filter(|b| b.in_range).count();
+
+        Self {
+            point: solution.point,
+            origin_distance: solution.origin_distance,
+            objective: solution.objective,
+            recount,
+            out_of_range: bots.len() - recount,
+            bots,
+        }
+    }
+
+    // The soft-constraint objective and the recount have to land on the same
+    // number, or one of the encodings is wrong.
+    pub fn agrees(&self) -> bool {
+        self.objective == self.recount
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Bot, Point};
+use std::fmt;
+use z3::ast::Int;
+
+// How distance is measured between a bot and a point. Every metric is built
+// from the three per-axis distances and only grows when one of them does,
+// which is what lets the octree bound whole cubes with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    // |dx| + |dy| + |dz|, the puzzle's own metric.
+    Manhattan,
+    // max(|dx|, |dy|, |dz|)
+    Chebyshev,
+    // dx² + dy² + dz², compared against r², so a bot's range is a ball.
+    // Nonlinear, so Z3 has a much harder time with it.
+    SquaredEuclidean,
+    // wx·|dx| + wy·|dy| + wz·|dz| with positive integer weights.
+    WeightedManhattan(i64, i64, i64),
+}
+
+impl Metric {
+    // manhattan, chebyshev, euclidean2 or weighted:wx,wy,wz
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "manhattan" => Some(Self::Manhattan),
+            "chebyshev" => Some(Self::Chebyshev),
+            "euclidean2" => Some(Self::SquaredEuclidean),
+            _ => {
+                let weights: Vec<i64> = s
+                    .strip_prefix("weighted:")?
+                    .split(',')
+                    .map(|w| w.trim().parse().ok())
+                    .collect::<Option<_>>()?;
+                match weights[..] {
+                    [wx, wy, wz] if wx > 0 && wy > 0 && wz > 0 => {
+                        Some(Self::WeightedManhattan(wx, wy, wz))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    // Combines per-axis distances (all non-negative) into a distance. Far
+    // points, like the edges of a zoomed-out view, saturate at i64::MAX
+    // rather than overflow, which still puts them out of every bot's range.
+    pub fn combine(&self, (dx, dy, dz): Point) -> i64 {
+        let weighted = |(wx, wy, wz): Point| {
+            wx.saturating_mul(dx)
+                .saturating_add(wy.saturating_mul(dy))
+                .saturating_add(wz.saturating_mul(dz))
+        };
+        match *self {
+            Self::Manhattan => weighted((1, 1, 1)),
+            Self::Chebyshev => dx.max(dy).max(dz),
+            Self::SquaredEuclidean => weighted((dx, dy, dz)),
+            Self::WeightedManhattan(wx, wy, wz) => weighted((wx, wy, wz)),
+        }
+    }
+
+    // The largest distance a bot with radius `r` still reaches. Input is run
+    // through `check` first, so this can't overflow.
+    pub fn reach(&self, r: i64) -> i64 {
+        self.checked_reach(r)
+            .expect("radius too large for the metric, see Metric::check")
+    }
+
+    fn checked_reach(&self, r: i64) -> Option<i64> {
+        match self {
+            Self::SquaredEuclidean => r.checked_mul(r),
+            _ => Some(r),
+        }
+    }
+
+    // Rejects bots whose reach doesn't fit in an i64 under this metric, which
+    // can only happen when it squares the radius.
+    pub fn check(&self, bots: &[Bot]) -> Result<(), String> {
+        match bots.iter().find(|b| self.checked_reach(b.r).is_none()) {
+            Some(b) => Err(format!(
+                "the bot at <{},{},{}> has radius {}, too large to square for {}",
+                b.x, b.y, b.z, b.r, self
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // Whether the Z3 encoding stays in linear integer arithmetic.
+    pub fn is_linear(&self) -> bool {
+        !matches!(self, Self::SquaredEuclidean)
+    }
+
+    // Linear forms f such that a point p is in range of a bot b exactly when
+    // |f · (p - b)| <= r for every f. Manhattan ranges are octahedra, so
+    // these are x+y+z, x+y-z, x-y+z and -x+y+z. None for squared Euclidean,
+    // whose ranges are balls.
+    pub fn forms(&self) -> Option<Vec<Point>> {
+        match *self {
+            Self::Manhattan => Self::WeightedManhattan(1, 1, 1).forms(),
+            Self::Chebyshev => Some(vec![(1, 0, 0), (0, 1, 0), (0, 0, 1)]),
+            Self::SquaredEuclidean => None,
+            Self::WeightedManhattan(wx, wy, wz) => Some(vec![
+                (wx, wy, wz),
+                (wx, wy, -wz),
+                (wx, -wy, wz),
+                (-wx, wy, wz),
+            ]),
+        }
+    }
+
+    // The plain Rust checker, used to count bots independently of Z3.
+    pub fn distance(&self, a: Point, b: Point) -> i64 {
+        self.combine(((a.0 - b.0).abs(), (a.1 - b.1).abs(), (a.2 - b.2).abs()))
+    }
+
+    pub fn in_range(&self, bot: &Bot, point: Point) -> bool {
+        self.distance((bot.x, bot.y, bot.z), point) <= self.reach(bot.r)
+    }
+
+    // The same distance as a Z3 term, from the target to a fixed point.
+    pub fn encode(&self, (tx, ty, tz): (&Int, &Int, &Int), (x, y, z): Point) -> Int {
+        let (dx, dy, dz) = (abs(tx, x), abs(ty, y), abs(tz, z));
+        match *self {
+            Self::Manhattan => &(&dx + &dy) + &dz,
+            Self::Chebyshev => {
+                let m = dx.ge(&dy).ite(&dx, &dy);
+                m.ge(&dz).ite(&m, &dz)
+            }
+            // Squaring makes the sign irrelevant, so skip the ite.
+            Self::SquaredEuclidean => {
+                let (dx, dy, dz) = (tx - x, ty - y, tz - z);
+                &(&(&dx * &dx) + &(&dy * &dy)) + &(&dz * &dz)
+            }
+            Self::WeightedManhattan(wx, wy, wz) => &(&(&dx * wx) + &(&dy * wy)) + &(&dz * wz),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Manhattan => write!(f, "manhattan"),
+            Self::Chebyshev => write!(f, "chebyshev"),
+            Self::SquaredEuclidean => write!(f, "euclidean2"),
+            Self::WeightedManhattan(wx, wy, wz) => write!(f, "weighted:{},{},{}", wx, wy, wz),
+        }
+    }
+}
+
+// Helper function to get the distance from a to b as an Int (via ge/ite).
+fn abs(a: &Int, b: i64) -> Int {
+    let b_val = Int::from_i64(b);
+    let diff = a - &b_val;
+    let neg_diff = &b_val - a;
+    a.ge(&b_val).ite(&diff, &neg_diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metric;
+    use crate::nanobots::{Bot, Point};
+    use z3::ast::{Ast, Int};
+
+    const METRICS: [Metric; 4] = [
+        Metric::Manhattan,
+        Metric::Chebyshev,
+        Metric::SquaredEuclidean,
+        Metric::WeightedManhattan(1, 2, 3),
+    ];
+
+    // A spread of offsets, so every axis is seen both ways round and each
+    // one is the largest somewhere.
+    const POINTS: [Point; 5] = [(0, 0, 0), (3, -4, 5), (-7, 2, 1), (1, 9, -2), (-5, -5, -5)];
+
+    #[test]
+    fn z3_encoding_matches_the_plain_distance() {
+        for metric in METRICS {
+            for target in POINTS {
+                for point in POINTS {
+                    let (tx, ty, tz) = (
+                        Int::from_i64(target.0),
+                        Int::from_i64(target.1),
+                        Int::from_i64(target.2),
+                    );
+                    let encoded = metric.encode((&tx, &ty, &tz), point).simplify();
+                    assert_eq!(
+                        encoded.as_i64(),
+                        Some(metric.distance(target, point)),
+                        "{} from {:?} to {:?}",
+                        metric,
+                        target,
+                        point
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn radii_too_large_to_square_are_rejected() {
+        let bot = |r| Bot {
+            x: 1,
+            y: 2,
+            z: 3,
+            r,
+        };
+        let huge = [bot(5), bot(i64::MAX / 2)];
+        assert!(Metric::SquaredEuclidean.check(&huge).is_err());
+        assert_eq!(Metric::Manhattan.check(&huge), Ok(()));
+        assert_eq!(
+            Metric::SquaredEuclidean.check(&[bot(3_000_000_000)]),
+            Ok(())
+        );
+        assert_eq!(
+            Metric::SquaredEuclidean.reach(3_000_000_000),
+            9_000_000_000_000_000_000
+        );
+    }
+}
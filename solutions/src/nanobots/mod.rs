@@ -0,0 +1,167 @@
+pub mod enumerate;
+pub mod explain;
+pub mod metric;
+pub mod octree;
+pub mod optimize;
+
+use crate::deadline::Deadline;
+use explain::Explanation;
+use metric::Metric;
+use serde::{Deserialize, Serialize};
+use z3::SatResult;
+
+pub type Point = (i64, i64, i64);
+
+// A way of finding the best point. Err says why there isn't one: Unsat if no
+// point exists, Unknown if Z3 gave up along the way or the deadline passed.
+pub type Solver = fn(&[Bot], Metric, Deadline) -> Result<Solution, SatResult>;
+
+#[derive(Deserialize, Debug)]
+pub struct Bot {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+    pub r: i64,
+}
+
+impl Bot {
+    // Parses a line of the original puzzle input: `pos=<x,y,z>, r=N`.
+    pub fn parse(line: &str) -> Self {
+        let nums: Vec<i64> = line
+            .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().expect("Invalid number in nanobot input"))
+            .collect();
+        match nums[..] {
+            [x, y, z, r] => Self { x, y, z, r },
+            _ => panic!("Invalid nanobot line: {}", line),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InputData {
+    pub bots: Vec<Bot>,
+}
+
+// The best point found, how many bots the search says can reach it, and its
+// distance to the origin under the chosen metric (the puzzle answer for
+// Manhattan). The count comes from the search itself so the report can check
+// it against an independent recount.
+#[derive(Debug, PartialEq)]
+pub struct Solution {
+    point: Point,
+    objective: usize,
+    origin_distance: i64,
+}
+
+impl Solution {
+    fn new(metric: Metric, point: Point, objective: usize) -> Self {
+        Self {
+            point,
+            objective,
+            origin_distance: metric.distance(point, (0, 0, 0)),
+        }
+    }
+}
+
+// AoC part 1: how many bots are in range of the one with the largest radius.
+pub fn strongest_in_range(bots: &[Bot], metric: Metric) -> usize {
+    let Some(strongest) = bots.iter().max_by_key(|b| b.r) else {
+        return 0;
+    };
+    bots.iter()
+        .filter(|b| metric.in_range(strongest, (b.x, b.y, b.z)))
+        .count()
+}
+
+// One mode's result, as written by --json. `status` is "sat", "unsat" or
+// "unknown", and only "sat" comes with a solution.
+#[derive(Serialize)]
+pub struct ModeResult {
+    pub mode: String,
+    pub elapsed_ms: f64,
+    pub status: String,
+    pub solution: Option<Explanation>,
+}
+
+impl ModeResult {
+    pub fn new(
+        bots: &[Bot],
+        metric: Metric,
+        mode: &str,
+        elapsed_ms: f64,
+        result: &Result<Solution, SatResult>,
+    ) -> Self {
+        let status = match result {
+            Ok(_) => "sat",
+            Err(SatResult::Unknown) => "unknown",
+            Err(_) => "unsat",
+        };
+        Self {
+            mode: mode.to_string(),
+            elapsed_ms,
+            status: status.to_string(),
+            solution: result
+                .as_ref()
+                .ok()
+                .map(|s| Explanation::new(bots, metric, s)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Output {
+    pub metric: String,
+    pub strongest_in_range: usize,
+    pub results: Vec<ModeResult>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{octree, optimize, Bot, InputData, Solver};
+    use crate::deadline::Deadline;
+    use crate::nanobots::metric::Metric;
+
+    fn json(input: &str) -> Vec<Bot> {
+        serde_json::from_str::<InputData>(input).unwrap().bots
+    }
+
+    fn text(input: &str) -> Vec<Bot> {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Bot::parse)
+            .collect()
+    }
+
+    // The two modes may settle on different points when several tie, but
+    // the count and the distance to the origin have to be the same.
+    #[test]
+    fn octree_and_optimize_agree_on_the_inputs() {
+        let inputs = [
+            (
+                "nanobots_sample.json",
+                json(include_str!("../../../inputs/nanobots_sample.json")),
+            ),
+            (
+                "nanobots_simple.json",
+                json(include_str!("../../../inputs/nanobots_simple.json")),
+            ),
+            (
+                "nanobots_sample.txt",
+                text(include_str!("../../../inputs/nanobots_sample.txt")),
+            ),
+        ];
+        for (name, bots) in &inputs {
+            let solve = |solver: Solver| solver(bots, Metric::Manhattan, Deadline::NONE).unwrap();
+            let (a, b) = (solve(optimize::solve), solve(octree::solve));
+            assert_eq!(
+                (a.objective, a.origin_distance),
+                (b.objective, b.origin_distance),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use super::optimize::solve_within;
+use super::{Bot, Metric, Point, Solution};
+use crate::deadline::Deadline;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use z3::SatResult;
+
+// Cubes this small (per side), or crossed by this few bot boundaries, are
+// handed to Z3 instead of being split again.
+const REFINE_SIZE: i64 = 16;
+const REFINE_BOTS: usize = 2;
+
+// An axis-aligned cube covering `min` up to `min + size - 1` on every axis.
+#[derive(Clone, Copy)]
+struct Cube {
+    min: Point,
+    size: i64,
+}
+
+impl Cube {
+    fn max(&self) -> Point {
+        let d = self.size - 1;
+        (self.min.0 + d, self.min.1 + d, self.min.2 + d)
+    }
+
+    // Distance from a point to the closest point in the cube.
+    fn nearest(&self, metric: Metric, (x, y, z): Point) -> i64 {
+        let max = self.max();
+        let axis = |v: i64, lo: i64, hi: i64| (lo - v).max(0) + (v - hi).max(0);
+        metric.combine((
+            axis(x, self.min.0, max.0),
+            axis(y, self.min.1, max.1),
+            axis(z, self.min.2, max.2),
+        ))
+    }
+
+    // The point in the cube closest to the given point.
+    fn closest(&self, (x, y, z): Point) -> Point {
+        let max = self.max();
+        (
+            x.clamp(self.min.0, max.0),
+            y.clamp(self.min.1, max.1),
+            z.clamp(self.min.2, max.2),
+        )
+    }
+
+    // Distance from a point to the farthest point in the cube.
+    fn farthest(&self, metric: Metric, (x, y, z): Point) -> i64 {
+        let max = self.max();
+        let axis = |v: i64, lo: i64, hi: i64| (v - lo).abs().max((hi - v).abs());
+        metric.combine((
+            axis(x, self.min.0, max.0),
+            axis(y, self.min.1, max.1),
+            axis(z, self.min.2, max.2),
+        ))
+    }
+
+    // Some point in the cube is in range of the bot.
+    fn touches(&self, metric: Metric, bot: &Bot) -> bool {
+        self.nearest(metric, (bot.x, bot.y, bot.z)) <= metric.reach(bot.r)
+    }
+
+    // Every point in the cube is in range of the bot.
+    fn inside(&self, metric: Metric, bot: &Bot) -> bool {
+        self.farthest(metric, (bot.x, bot.y, bot.z)) <= metric.reach(bot.r)
+    }
+
+    fn children(&self) -> Vec<Cube> {
+        let half = self.size / 2;
+        let mut children = Vec::with_capacity(8);
+        for dx in [0, half] {
+            for dy in [0, half] {
+                for dz in [0, half] {
+                    children.push(Cube {
+                        min: (self.min.0 + dx, self.min.1 + dy, self.min.2 + dz),
+                        size: half,
+                    });
+                }
+            }
+        }
+        children
+    }
+}
+
+// A queue entry is either a cube still to explore, with an upper bound on
+// the bots in range and a lower bound on the origin distance, or the exact
+// best point Z3 found inside a small cube along with its count.
+struct Entry {
+    count: usize,
+    origin_distance: i64,
+    cube: Cube,
+    solved: Option<Point>,
+}
+
+impl Entry {
+    // Most bots first, then closest to the origin, then solved points and
+    // smaller cubes so ties finish quickly.
+    fn key(&self) -> (usize, Reverse<i64>, bool, Reverse<i64>) {
+        (
+            self.count,
+            Reverse(self.origin_distance),
+            self.solved.is_some(),
+            Reverse(self.cube.size),
+        )
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+// Best-first search over an octree. Every cube's score is an upper bound for
+// the points inside it, so the first solved point to come off the queue can't
+// be beaten by anything left on it. A refinement Z3 can't finish leaves the
+// whole search Unknown, since the cube it gave up on might hold the answer,
+// and so does running past `deadline`.
+pub fn solve(bots: &[Bot], metric: Metric, deadline: Deadline) -> Result<Solution, SatResult> {
+    if bots.is_empty() {
+        return Ok(Solution::new(metric, (0, 0, 0), 0));
+    }
+
+    let mut queue = BinaryHeap::new();
+    queue.push(entry(bots, metric, bounding_cube(bots), None));
+
+    while let Some(Entry {
+        count,
+        cube,
+        solved,
+        ..
+    }) = queue.pop()
+    {
+        if let Some(point) = solved {
+            return Ok(Solution::new(metric, point, count));
+        }
+        if deadline.passed() {
+            return Err(SatResult::Unknown);
+        }
+
+        // Bots that cover the whole cube count the same everywhere in it, so
+        // Z3 only has to weigh up the ones that cover part of it.
+        let partial: Vec<&Bot> = bots
+            .iter()
+            .filter(|b| cube.touches(metric, b) && !cube.inside(metric, b))
+            .collect();
+
+        let covering = bots.iter().filter(|b| cube.inside(metric, b)).count();
+        if partial.is_empty() {
+            // Every point in the cube scores the same, so take the one
+            // closest to the origin.
+            let point = cube.closest((0, 0, 0));
+            queue.push(entry(bots, metric, cube, Some((point, covering))));
+        } else if cube.size <= REFINE_SIZE || partial.len() <= REFINE_BOTS {
+            let (point, satisfied) =
+                solve_within(&partial, metric, Some((cube.min, cube.max())), deadline)?;
+            queue.push(entry(
+                bots,
+                metric,
+                cube,
+                Some((point, covering + satisfied)),
+            ));
+        } else {
+            for child in cube.children() {
+                queue.push(entry(bots, metric, child, None));
+            }
+        }
+    }
+
+    Err(SatResult::Unsat)
+}
+
+// A solved entry takes the count the cube geometry and Z3 worked out for its
+// point; the report recounts it independently afterwards.
+fn entry(bots: &[Bot], metric: Metric, cube: Cube, solved: Option<(Point, usize)>) -> Entry {
+    match solved {
+        Some((point, count)) => Entry {
+            count,
+            origin_distance: metric.distance(point, (0, 0, 0)),
+            cube,
+            solved: Some(point),
+        },
+        None => Entry {
+            count: bots.iter().filter(|b| cube.touches(metric, b)).count(),
+            origin_distance: cube.nearest(metric, (0, 0, 0)),
+            cube,
+            solved: None,
+        },
+    }
+}
+
+// A power-of-two cube around every bot and the origin. Pulling a point
+// towards this box shrinks every axis distance to the bots and the origin, so
+// under any of the metrics the best point is always inside.
+fn bounding_cube(bots: &[Bot]) -> Cube {
+    let mut lo = (0, 0, 0);
+    let mut hi = (0, 0, 0);
+    for bot in bots {
+        lo = (lo.0.min(bot.x), lo.1.min(bot.y), lo.2.min(bot.z));
+        hi = (hi.0.max(bot.x), hi.1.max(bot.y), hi.2.max(bot.z));
+    }
+
+    let extent = (hi.0 - lo.0).max(hi.1 - lo.1).max(hi.2 - lo.2) + 1;
+    let mut size = 1;
+    while size < extent {
+        size *= 2;
+    }
+    Cube { min: lo, size }
+}
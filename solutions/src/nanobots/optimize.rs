@@ -0,0 +1,114 @@
+use super::{Bot, Metric, Point, Solution};
+use crate::deadline::Deadline;
+use z3::ast::{Bool, Int};
+use z3::{Optimize, SatResult};
+
+// The whole field in one Optimize.
+pub fn solve(bots: &[Bot], metric: Metric, deadline: Deadline) -> Result<Solution, SatResult> {
+    let refs: Vec<&Bot> = bots.iter().collect();
+
+    // The best point never lies outside the box around the bots and the
+    // origin. Linear metrics don't need telling, but without it the
+    // nonlinear search wanders off and never comes back.
+    let bounds = (!metric.is_linear()).then(|| {
+        let mut lo = (0, 0, 0);
+        let mut hi = (0, 0, 0);
+        for bot in bots {
+            lo = (lo.0.min(bot.x), lo.1.min(bot.y), lo.2.min(bot.z));
+            hi = (hi.0.max(bot.x), hi.1.max(bot.y), hi.2.max(bot.z));
+        }
+        (lo, hi)
+    });
+    let (point, satisfied) = solve_within(&refs, metric, bounds, deadline)?;
+    Ok(Solution::new(metric, point, satisfied))
+}
+
+// Maximizes the number of `bots` in range, then minimizes the distance to the
+// origin. Returns the point and how many of `bots` Z3 has in range there.
+// With `bounds` set, the target is kept inside the box from `min` to `max`
+// (inclusive), which is how the octree search refines small boxes. Err holds
+// the check result when Z3 has no answer, and is Unknown once `deadline`
+// passes.
+pub fn solve_within(
+    bots: &[&Bot],
+    metric: Metric,
+    bounds: Option<(Point, Point)>,
+    deadline: Deadline,
+) -> Result<(Point, usize), SatResult> {
+    let opt = Optimize::new();
+
+    // Target Coordinates
+    let tx = Int::new_const("tx");
+    let ty = Int::new_const("ty");
+    let tz = Int::new_const("tz");
+    let target = (&tx, &ty, &tz);
+
+    if let Some((min, max)) = bounds {
+        for (t, lo, hi) in [
+            (&tx, min.0, max.0),
+            (&ty, min.1, max.1),
+            (&tz, min.2, max.2),
+        ] {
+            opt.assert(&t.ge(lo));
+            opt.assert(&t.le(hi));
+        }
+    }
+
+    // Maximize In-Range Bots
+    let mut in_range: Vec<Bool> = Vec::with_capacity(bots.len());
+    for bot in bots {
+        let d = metric.encode(target, (bot.x, bot.y, bot.z));
+
+        // In Range: Dist <= r (or r² for squared Euclidean)
+        let bot_in_range = d.le(Int::from_i64(metric.reach(bot.r)));
+
+        // Soft constraint: prefer satisfying this (weight 1)
+        opt.assert_soft(&bot_in_range, 1, None);
+        in_range.push(bot_in_range);
+    }
+
+    // Secondary Objective: Minimize Distance to Origin
+    let dist_origin = metric.encode(target, (0, 0, 0));
+    opt.minimize(&dist_origin);
+
+    match opt.check(&[]) {
+        SatResult::Sat => {}
+        result => return Err(result),
+    }
+
+    let read = |opt: &Optimize| {
+        let model = opt.get_model().unwrap();
+        let x = numerals::eval_i64(&model, &tx);
+        let y = numerals::eval_i64(&model, &ty);
+        let z = numerals::eval_i64(&model, &tz);
+        let satisfied = in_range
+            .iter()
+            .filter(|b| model.eval(*b, true).unwrap().as_bool().unwrap())
+            .count();
+        ((x, y, z), satisfied)
+    };
+    let (mut point, satisfied) = read(&opt);
+
+    // Z3 only promises an optimum for linear objectives. For a nonlinear
+    // metric, hold on to the count and keep asking for a strictly closer
+    // point until there isn't one. If Z3 gives up first, the point found so
+    // far may not be the closest, so that is an Unknown too.
+    if !metric.is_linear() {
+        let counted: Vec<(&Bool, i32)> = in_range.iter().map(|b| (b, 1)).collect();
+        opt.assert(&Bool::pb_ge(&counted, satisfied as i32));
+        loop {
+            if deadline.passed() {
+                return Err(SatResult::Unknown);
+            }
+            let current = metric.distance(point, (0, 0, 0));
+            opt.assert(&dist_origin.lt(Int::from_i64(current)));
+            match opt.check(&[]) {
+                SatResult::Sat => point = read(&opt).0,
+                SatResult::Unsat => break,
+                SatResult::Unknown => return Err(SatResult::Unknown),
+            }
+        }
+    }
+
+    Ok((point, satisfied))
+}
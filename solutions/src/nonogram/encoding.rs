@@ -0,0 +1,471 @@
+use super::prepass::start_ranges;
+use super::{exact_clues, LineClues};
+use std::collections::VecDeque;
+use std::ops::{BitAnd, BitOr};
+use z3::ast::{Ast, Bool, Int};
+use z3::Solver;
+
+// How each line's clues are turned into constraints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    // An Int start variable per block and a disjunction per cell.
+    Int,
+    // A deterministic automaton per line with a Bool per (position, state).
+    Automaton,
+}
+
+impl Encoding {
+    pub fn parse(name: &str) -> Option<Encoding> {
+        match name {
+            "int" => Some(Encoding::Int),
+            "automaton" => Some(Encoding::Automaton),
+            _ => None,
+        }
+    }
+}
+
+// Hands a line to the chosen encoding. With the pre-pass on, lines that were
+// solved completely are skipped since their cells are already asserted as
+// facts, and the Int encoding gets block start ranges narrowed using what we
+// already know. Lines with wildcards always use `constrain_line_wildcard`,
+// since neither encoding can be built without knowing every block. They are
+// never skipped: the pre-pass doesn't check them, so their cells may have
+// been filled in by crossing lines without ever matching their own clues.
+pub fn add_line(
+    solver: &Solver,
+    line: &[&Bool],
+    clues: &LineClues,
+    cells: &[Option<bool>],
+    prepass: bool,
+    encoding: Encoding,
+    prefix: &str,
+) {
+    let Some(clues) = exact_clues(clues) else {
+        constrain_line_wildcard(solver, line, clues, prefix);
+        return;
+    };
+    if prepass && cells.iter().all(|c| c.is_some()) {
+        return;
+    }
+    let clues = clues.as_slice();
+
+    if encoding == Encoding::Automaton {
+        constrain_line_automaton(solver, line, clues, prefix);
+        return;
+    }
+
+    // Without any knowledge a block may start anywhere it fits.
+    let loose: Vec<(i64, i64)> = clues
+        .iter()
+        .map(|&len| (0, line.len() as i64 - len as i64))
+        .collect();
+    let ranges = match start_ranges(clues, cells) {
+        Some(ranges) if prepass => ranges
+            .into_iter()
+            .map(|(lo, hi)| (lo as i64, hi as i64))
+            .collect(),
+        _ => loose,
+    };
+    constrain_line(solver, line, clues, &ranges, prefix);
+}
+
+fn constrain_line(
+    solver: &Solver,
+    line: &[&Bool],
+    clues: &[usize],
+    ranges: &[(i64, i64)],
+    prefix: &str,
+) {
+    let clues_len = clues.len();
+
+    // Create start position variables for each block
+    let starts: Vec<Int> = (0..clues_len)
+        .map(|i| Int::new_const(format!("{}_s_{}", prefix, i).as_str()))
+        .collect();
+
+    // Constrain start positions for each block
+    for (i, (start, &(lo, hi))) in starts.iter().zip(ranges.iter()).enumerate() {
+        solver.assert(start.ge(lo));
+        solver.assert(start.le(hi));
+
+        // Ensure blocks don't overlap (at least 1 gap between blocks)
+        if i > 0 {
+            let prev_len = clues[i - 1] as i64;
+            solver.assert(start.ge(&(&starts[i - 1] + prev_len + 1)));
+        }
+    }
+
+    // If there are no clues, we are done.
+    if clues.is_empty() {
+        // Empty line: all cells must be false
+        for cell in line {
+            solver.assert(Ast::eq(*cell, Bool::from_bool(false)));
+        }
+        return;
+    }
+
+    // Otherwise, constrain each cell. Each cell is within a range if it's between the start and the start's length. We or together all of the starts.
+    // Blocks whose start range can never reach the cell are left out of the disjunction.
+    for (j, cell) in line.iter().enumerate() {
+        let j_int = Int::from_i64(j as i64);
+        let cell_constraint = starts
+            .iter()
+            .zip(clues.iter())
+            .zip(ranges.iter())
+            .filter(|((_, &block_len), &(lo, hi))| {
+                lo <= j as i64 && (j as i64) < hi + block_len as i64
+            })
+            .map(|((start, &block_len), _)| {
+                let len = block_len as i64;
+                start.le(&j_int).bitand(&j_int.lt(&(start + len)))
+            })
+            .reduce(|acc, cond| acc.bitor(&cond))
+            .unwrap_or_else(|| Bool::from_bool(false));
+
+        solver.assert(Ast::eq(*cell, &cell_constraint));
+    }
+}
+
+// Lines with wildcards. Every block gets an Int start and an Int length, and
+// unknown lengths are left for Z3 to choose. When the whole clue list is
+// unknown we allow as many blocks as could fit and give each a Bool saying
+// whether it is used; used blocks always come first, so the block count is
+// chosen too.
+fn constrain_line_wildcard(solver: &Solver, line: &[&Bool], clues: &LineClues, prefix: &str) {
+    let cells_len = line.len() as i64;
+
+    // (known length, must be used) for each block
+    let blocks: Vec<(Option<usize>, bool)> = match clues {
+        Some(clues) => clues.iter().map(|&len| (len, true)).collect(),
+        None => vec![(None, false); line.len().div_ceil(2)],
+    };
+
+    let mut starts: Vec<Int> = Vec::new();
+    let mut lens: Vec<Int> = Vec::new();
+    let mut used: Vec<Bool> = Vec::new();
+    for (i, &(known_len, required)) in blocks.iter().enumerate() {
+        let start = Int::new_const(format!("{}_s_{}", prefix, i).as_str());
+        let len = match known_len {
+            Some(len) => Int::from_i64(len as i64),
+            None => {
+                let len = Int::new_const(format!("{}_len_{}", prefix, i).as_str());
+                solver.assert(len.ge(1));
+                len
+            }
+        };
+        let is_used = if required {
+            Bool::from_bool(true)
+        } else {
+            Bool::new_const(format!("{}_used_{}", prefix, i).as_str())
+        };
+
+        // A used block has to fit in the line.
+        solver.assert(is_used.implies(start.ge(0).bitand(&(&start + &len).le(cells_len))));
+
+        // Ensure blocks don't overlap (at least 1 gap between blocks), and
+        // only use a block if the one before it is used.
+        if i > 0 {
+            let after_prev = start.ge(&(&starts[i - 1] + &lens[i - 1] + 1));
+            solver.assert(is_used.implies((&used[i - 1]).bitand(&after_prev)));
+        }
+
+        starts.push(start);
+        lens.push(len);
+        used.push(is_used);
+    }
+
+    // A cell is filled exactly when a used block covers it.
+    for (j, cell) in line.iter().enumerate() {
+        let j_int = Int::from_i64(j as i64);
+        let cell_constraint = starts
+            .iter()
+            .zip(lens.iter())
+            .zip(used.iter())
+            .map(|((start, len), is_used)| {
+                is_used
+                    .bitand(&start.le(&j_int))
+                    .bitand(&j_int.lt(&(start + len)))
+            })
+            .reduce(|acc, cond| acc.bitor(&cond))
+            .unwrap_or_else(|| Bool::from_bool(false));
+
+        solver.assert(Ast::eq(*cell, &cell_constraint));
+    }
+}
+
+// A state in the line automaton. The automaton reads the line one cell at a
+// time and accepts exactly the lines that match the clues.
+struct State {
+    on_empty: Option<usize>,
+    on_filled: Option<usize>,
+    accepting: bool,
+}
+
+// Builds the automaton for 0* 1^c1 0+ 1^c2 ... 1^ck 0*. State 0 reads the
+// leading empty cells, then each block gets one state per filled cell
+// followed by a gap state that reads the empty cells after it.
+fn line_automaton(clues: &[usize]) -> Vec<State> {
+    let mut states = vec![State {
+        on_empty: Some(0),
+        on_filled: None,
+        accepting: clues.is_empty(),
+    }];
+
+    for (i, &len) in clues.iter().enumerate() {
+        // The state we come from starts the block on a filled cell.
+        let from = states.len() - 1;
+        states[from].on_filled = Some(states.len());
+
+        for j in 1..=len {
+            let idx = states.len();
+            states.push(State {
+                on_empty: None,
+                on_filled: None,
+                accepting: false,
+            });
+            if j < len {
+                states[idx].on_filled = Some(idx + 1);
+            }
+        }
+
+        // The last cell of the block must be followed by an empty cell (or
+        // the end of the line) and then we sit in the gap.
+        let last = states.len() - 1;
+        let gap = states.len();
+        states[last].on_empty = Some(gap);
+        states.push(State {
+            on_empty: Some(gap),
+            on_filled: None,
+            accepting: i == clues.len() - 1,
+        });
+        states[last].accepting = i == clues.len() - 1;
+    }
+
+    states
+}
+
+// Shortest number of cells needed to walk between states, following the
+// edges forwards (from the start) or backwards (to an accepting state).
+fn distances(states: &[State], forward: bool) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; states.len()];
+    let mut queue = VecDeque::new();
+    if forward {
+        dist[0] = 0;
+        queue.push_back(0);
+    } else {
+        for (q, state) in states.iter().enumerate() {
+            if state.accepting {
+                dist[q] = 0;
+                queue.push_back(q);
+            }
+        }
+    }
+
+    while let Some(q) = queue.pop_front() {
+        for (from, state) in states.iter().enumerate() {
+            for (a, b) in [(from, state.on_empty), (from, state.on_filled)] {
+                let (src, dst) = match b {
+                    Some(b) if forward => (a, b),
+                    Some(b) => (b, a),
+                    None => continue,
+                };
+                if src == q && dist[dst] == usize::MAX {
+                    dist[dst] = dist[q] + 1;
+                    queue.push_back(dst);
+                }
+            }
+        }
+    }
+    dist
+}
+
+// Boolean-only encoding. at[p][q] is true when the automaton is in state q
+// after reading the first p cells. Each at[p + 1][q] is defined as the OR of
+// the transitions that lead into it, so there is exactly one path through the
+// automaton, and it has to end in an accepting state.
+fn constrain_line_automaton(solver: &Solver, line: &[&Bool], clues: &[usize], prefix: &str) {
+    let n = line.len();
+    let states = line_automaton(clues);
+    let from_start = distances(&states, true);
+    let to_accept = distances(&states, false);
+
+    // Only create variables for states that can actually be reached at p and
+    // still finish the line in time.
+    let reachable = |p: usize, q: usize| {
+        from_start[q] <= p && to_accept[q] != usize::MAX && p + to_accept[q] <= n
+    };
+
+    let mut at: Vec<Vec<Option<Bool>>> = Vec::with_capacity(n + 1);
+    at.push(
+        (0..states.len())
+            .map(|q| (q == 0).then(|| Bool::from_bool(true)))
+            .collect(),
+    );
+
+    for (p, cell) in line.iter().enumerate() {
+        let mut next = Vec::with_capacity(states.len());
+        for q in 0..states.len() {
+            if !reachable(p + 1, q) {
+                next.push(None);
+                continue;
+            }
+
+            let incoming = states
+                .iter()
+                .enumerate()
+                .filter_map(|(from, state)| {
+                    let prev = at[p][from].as_ref()?;
+                    if state.on_empty == Some(q) {
+                        Some(prev.bitand(cell.not()))
+                    } else if state.on_filled == Some(q) {
+                        Some(prev.bitand(*cell))
+                    } else {
+                        None
+                    }
+                })
+                .reduce(|acc, t| acc.bitor(&t))
+                .unwrap_or_else(|| Bool::from_bool(false));
+
+            let var = Bool::new_const(format!("{}_q_{}_{}", prefix, p + 1, q));
+            solver.assert(Ast::eq(&var, &incoming));
+            next.push(Some(var));
+        }
+        at.push(next);
+    }
+
+    let accepted = states
+        .iter()
+        .zip(at[n].iter())
+        .filter(|(state, _)| state.accepting)
+        .filter_map(|(_, var)| var.clone())
+        .reduce(|acc, v| acc.bitor(&v))
+        .unwrap_or_else(|| Bool::from_bool(false));
+    solver.assert(&accepted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_line, line_automaton, Encoding};
+    use crate::nonogram::{runs, LineClues};
+    use z3::ast::{Ast, Bool};
+    use z3::{SatResult, Solver};
+
+    // Lines are written one character per cell: `#` filled and `.` empty.
+    fn line(s: &str) -> Vec<bool> {
+        s.chars().map(|c| c == '#').collect()
+    }
+
+    // Runs the automaton by hand, without Z3.
+    fn accepts(clues: &[usize], s: &str) -> bool {
+        let states = line_automaton(clues);
+        let mut q = 0;
+        for filled in line(s) {
+            let next = if filled {
+                states[q].on_filled
+            } else {
+                states[q].on_empty
+            };
+            match next {
+                Some(next) => q = next,
+                None => return false,
+            }
+        }
+        states[q].accepting
+    }
+
+    // Checks every line of 0 to 5 cells against the encoding of `clues`,
+    // one encoding per length and a push/pop scope per line.
+    fn check_every_line(encoding: Encoding, clues: &LineClues) {
+        for n in 0..=5 {
+            let solver = Solver::new();
+            let vars: Vec<Bool> = (0..n)
+                .map(|j| Bool::new_const(format!("x_{}", j)))
+                .collect();
+            let line: Vec<&Bool> = vars.iter().collect();
+            add_line(&solver, &line, clues, &vec![None; n], false, encoding, "t");
+
+            for bits in 0..1u32 << n {
+                let cells: Vec<bool> = (0..n).map(|j| bits >> j & 1 == 1).collect();
+                solver.push();
+                for (var, &filled) in vars.iter().zip(&cells) {
+                    solver.assert(Ast::eq(var, Bool::from_bool(filled)));
+                }
+                assert_eq!(
+                    solver.check() == SatResult::Sat,
+                    matches(clues, &cells),
+                    "{:?} {:?} {:?}",
+                    encoding,
+                    clues,
+                    cells
+                );
+                solver.pop(1);
+            }
+        }
+    }
+
+    // Whether a line matches clues that may hold wildcards.
+    fn matches(clues: &LineClues, cells: &[bool]) -> bool {
+        let Some(clues) = clues else {
+            return true;
+        };
+        let found = runs(cells);
+        found.len() == clues.len()
+            && found
+                .iter()
+                .zip(clues)
+                .all(|(&len, clue)| clue.is_none_or(|clue| clue == len))
+    }
+
+    #[test]
+    fn automaton_accepts_only_matching_lines() {
+        assert!(accepts(&[1], "#"));
+        assert!(accepts(&[1], "..#."));
+        assert!(accepts(&[2, 1], "##.#"));
+        assert!(accepts(&[2, 1], ".##..#."));
+        assert!(!accepts(&[2, 1], "###"));
+        assert!(!accepts(&[2, 1], "####"));
+        assert!(!accepts(&[2, 1], "#.##"));
+        assert!(!accepts(&[2], "#"));
+        assert!(!accepts(&[1], ""));
+    }
+
+    #[test]
+    fn empty_clue_accepts_only_empty_lines() {
+        assert!(accepts(&[], ""));
+        assert!(accepts(&[], "..."));
+        assert!(!accepts(&[], ".#."));
+        for encoding in [Encoding::Int, Encoding::Automaton] {
+            check_every_line(encoding, &Some(vec![]));
+        }
+    }
+
+    #[test]
+    fn both_encodings_allow_exactly_the_matching_lines() {
+        let clue_lists: [&[usize]; 6] = [&[1], &[2], &[1, 1], &[3], &[1, 2], &[2, 1, 1]];
+        for clues in clue_lists {
+            let clues: LineClues = Some(clues.iter().map(|&len| Some(len)).collect());
+            for encoding in [Encoding::Int, Encoding::Automaton] {
+                check_every_line(encoding, &clues);
+            }
+        }
+    }
+
+    // Wildcard lines get the same encoding whichever one is chosen.
+    #[test]
+    fn a_fully_wildcard_line_allows_anything() {
+        check_every_line(Encoding::Int, &None);
+    }
+
+    #[test]
+    fn a_partially_wildcard_line_fixes_what_is_known() {
+        let clue_lists = [
+            vec![Some(2), None],
+            vec![None, Some(1)],
+            vec![None],
+            vec![None, None],
+        ];
+        for clues in clue_lists {
+            check_every_line(Encoding::Int, &Some(clues));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+pub mod encoding;
+pub mod prepass;
+pub mod validate;
+
+use encoding::{add_line, Encoding};
+use serde::Deserialize;
+use z3::ast::{Ast, Bool};
+use z3::Solver;
+
+#[derive(Deserialize, Debug)]
+pub struct NonogramPuzzle {
+    pub rows: usize,
+    pub cols: usize,
+    pub row_clues: Vec<LineClues>,
+    pub col_clues: Vec<LineClues>,
+}
+
+// Clues for one line. A `null` block is a wildcard whose length is unknown,
+// and a `null` line means even the number of blocks is unknown.
+pub type LineClues = Option<Vec<Option<usize>>>;
+
+// The clues of a line if every block length is known.
+pub fn exact_clues(clues: &LineClues) -> Option<Vec<usize>> {
+    clues.as_ref()?.iter().copied().collect()
+}
+
+// The clue that describes a line of filled cells.
+pub fn runs(line: &[bool]) -> Vec<usize> {
+    line.split(|filled| !filled)
+        .map(|run| run.len())
+        .filter(|&len| len > 0)
+        .collect()
+}
+
+// How the model is built.
+#[derive(Clone, Copy)]
+pub struct Options {
+    // Whether `known` comes from the line pre-pass, so solved lines can be
+    // skipped and block ranges narrowed.
+    pub prepass: bool,
+    pub encoding: Encoding,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            prepass: true,
+            encoding: Encoding::Int,
+        }
+    }
+}
+
+// Builds the solver and grid variables for a puzzle using the chosen encoding.
+// Cells in `known` are asserted as facts.
+pub fn build_model(
+    puzzle: &NonogramPuzzle,
+    known: &[Vec<Option<bool>>],
+    options: &Options,
+) -> (Solver, Vec<Vec<Bool>>) {
+    let solver = Solver::new();
+
+    // Grid Variables: grid[r][c] -> Bool
+    let grid = (0..puzzle.rows)
+        .map(|r| {
+            (0..puzzle.cols)
+                .map(|c| Bool::new_const(format!("g_{}_{}", r, c).as_str()))
+                .collect()
+        })
+        .collect::<Vec<Vec<_>>>();
+
+    // Anything the pre-pass worked out becomes a plain fact.
+    for (r, row) in known.iter().enumerate() {
+        for (c, cell) in row.iter().enumerate() {
+            if let Some(filled) = cell {
+                solver.assert(Ast::eq(&grid[r][c], Bool::from_bool(*filled)));
+            }
+        }
+    }
+
+    // Add constraints for each row.
+    for (r, clues) in puzzle.row_clues.iter().enumerate() {
+        let line: Vec<&Bool> = grid[r].iter().collect();
+        let prefix = format!("r{}", r);
+        add_line(
+            &solver,
+            &line,
+            clues,
+            &known[r],
+            options.prepass,
+            options.encoding,
+            &prefix,
+        );
+    }
+
+    // Add constraints for each column.
+    for (c, clues) in puzzle.col_clues.iter().enumerate() {
+        let line: Vec<&Bool> = (0..puzzle.rows).map(|r| &grid[r][c]).collect();
+        let cells: Vec<Option<bool>> = (0..puzzle.rows).map(|r| known[r][c]).collect();
+        let prefix = format!("c{}", c);
+        add_line(
+            &solver,
+            &line,
+            clues,
+            &cells,
+            options.prepass,
+            options.encoding,
+            &prefix,
+        );
+    }
+
+    (solver, grid)
+}
@@ -0,0 +1,291 @@
+use super::{exact_clues, NonogramPuzzle};
+use crate::deadline::Deadline;
+
+// Why the pre-pass stopped short of its fixpoint.
+#[derive(Debug, PartialEq)]
+pub enum Stopped {
+    // This line (e.g. "row 3") has no placement that fits its clues.
+    Contradiction(String),
+    // The deadline passed. A fully known line may not have been checked
+    // against its own clues yet, so `known` mustn't be used to build a model.
+    Deadline,
+}
+
+// Before building anything in Z3 we solve as much as we can the way a person
+// would: line by line, using the overlap and edge-forcing rules, until no line
+// changes any more. Lines with wildcards are left alone.
+pub fn line_prepass(
+    puzzle: &NonogramPuzzle,
+    known: &mut [Vec<Option<bool>>],
+    deadline: Deadline,
+) -> Result<(), Stopped> {
+    loop {
+        if deadline.passed() {
+            return Err(Stopped::Deadline);
+        }
+        let mut changed = false;
+
+        for (r, clues) in puzzle.row_clues.iter().enumerate() {
+            let Some(clues) = exact_clues(clues) else {
+                continue;
+            };
+            let cells = known[r].clone();
+            let solved = solve_line(&clues, &cells)
+                .ok_or_else(|| Stopped::Contradiction(format!("row {}", r)))?;
+            for (c, cell) in solved.into_iter().enumerate() {
+                if known[r][c] != cell {
+                    known[r][c] = cell;
+                    changed = true;
+                }
+            }
+        }
+
+        for (c, clues) in puzzle.col_clues.iter().enumerate() {
+            let Some(clues) = exact_clues(clues) else {
+                continue;
+            };
+            let cells: Vec<Option<bool>> = (0..puzzle.rows).map(|r| known[r][c]).collect();
+            let solved = solve_line(&clues, &cells)
+                .ok_or_else(|| Stopped::Contradiction(format!("column {}", c)))?;
+            for (r, cell) in solved.into_iter().enumerate() {
+                if known[r][c] != cell {
+                    known[r][c] = cell;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            return Ok(());
+        }
+    }
+}
+
+// Applies the overlap and edge-forcing rules to a single line. Returns None if
+// the clues can't be placed at all given the known cells.
+fn solve_line(clues: &[usize], cells: &[Option<bool>]) -> Option<Vec<Option<bool>>> {
+    let ranges = start_ranges(clues, cells)?;
+    let mut solved = cells.to_vec();
+
+    for (j, cell) in solved.iter_mut().enumerate() {
+        // Overlap: the block covers this cell wherever it is placed.
+        let always_filled = clues
+            .iter()
+            .zip(ranges.iter())
+            .any(|(&len, &(lo, hi))| hi <= j && j < lo + len);
+        // No block can reach this cell.
+        let never_filled = clues
+            .iter()
+            .zip(ranges.iter())
+            .all(|(&len, &(lo, hi))| j < lo || hi + len <= j);
+
+        if always_filled {
+            *cell = Some(true);
+        } else if never_filled {
+            *cell = Some(false);
+        }
+    }
+
+    Some(solved)
+}
+
+// The range of valid start positions for each block, found from the leftmost
+// and rightmost placements that agree with the known cells. Known cells force
+// blocks away from the edges, which is where the edge-forcing comes from.
+pub fn start_ranges(clues: &[usize], cells: &[Option<bool>]) -> Option<Vec<(usize, usize)>> {
+    let n = cells.len();
+    let left = leftmost(clues, cells)?;
+
+    // The rightmost placement is the leftmost placement of the reversed line.
+    let rev_clues: Vec<usize> = clues.iter().rev().copied().collect();
+    let rev_cells: Vec<Option<bool>> = cells.iter().rev().copied().collect();
+    let rev_left = leftmost(&rev_clues, &rev_cells)?;
+
+    let ranges = (0..clues.len())
+        .map(|i| {
+            let right = n - (rev_left[clues.len() - 1 - i] + clues[i]);
+            (left[i], right)
+        })
+        .collect();
+    Some(ranges)
+}
+
+// Finds the leftmost start of each block that is consistent with the known
+// cells, backtracking when a later block can't be placed.
+fn leftmost(clues: &[usize], cells: &[Option<bool>]) -> Option<Vec<usize>> {
+    fn place(
+        clues: &[usize],
+        cells: &[Option<bool>],
+        i: usize,
+        from: usize,
+        starts: &mut Vec<usize>,
+        failed: &mut Vec<Vec<bool>>,
+    ) -> bool {
+        let n = cells.len();
+
+        // Every block is placed. Any filled cell left over is uncovered.
+        if i == clues.len() {
+            return cells[from.min(n)..].iter().all(|c| *c != Some(true));
+        }
+        if from > n || failed[i][from] {
+            return false;
+        }
+
+        let len = clues[i];
+        let mut s = from;
+        while s + len <= n {
+            // Skipping over a filled cell would leave it uncovered.
+            if s > from && cells[s - 1] == Some(true) {
+                break;
+            }
+
+            let fits = cells[s..s + len].iter().all(|c| *c != Some(false))
+                && cells.get(s + len) != Some(&Some(true));
+            if fits && place(clues, cells, i + 1, s + len + 1, starts, failed) {
+                starts[i] = s;
+                return true;
+            }
+            s += 1;
+        }
+
+        failed[i][from] = true;
+        false
+    }
+
+    let mut starts = vec![0; clues.len()];
+    let mut failed = vec![vec![false; cells.len() + 1]; clues.len()];
+    place(clues, cells, 0, 0, &mut starts, &mut failed).then_some(starts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{leftmost, line_prepass, solve_line, start_ranges, Stopped};
+    use crate::deadline::Deadline;
+    use crate::nonogram::NonogramPuzzle;
+    use std::time::Duration;
+
+    // Lines are written one character per cell: `#` filled, `.` empty and
+    // `?` unknown.
+    fn line(s: &str) -> Vec<Option<bool>> {
+        s.chars()
+            .map(|c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn solve(clues: &[usize], cells: &str) -> Option<String> {
+        let solved = solve_line(clues, &line(cells))?;
+        Some(
+            solved
+                .iter()
+                .map(|cell| match cell {
+                    Some(true) => '#',
+                    Some(false) => '.',
+                    None => '?',
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn overlap_fills_the_middle() {
+        assert_eq!(solve(&[3], "?????").as_deref(), Some("??#??"));
+        assert_eq!(solve(&[4], "?????").as_deref(), Some("?###?"));
+        assert_eq!(solve(&[2], "?????").as_deref(), Some("?????"));
+    }
+
+    #[test]
+    fn full_lines_are_solved() {
+        assert_eq!(solve(&[5], "?????").as_deref(), Some("#####"));
+        assert_eq!(solve(&[2, 2], "?????").as_deref(), Some("##.##"));
+        assert_eq!(solve(&[1, 1, 1], "?????").as_deref(), Some("#.#.#"));
+    }
+
+    #[test]
+    fn edges_force_blocks() {
+        // A filled first cell pins the block to the edge.
+        assert_eq!(solve(&[3], "#????").as_deref(), Some("###.."));
+        assert_eq!(solve(&[3], "????#").as_deref(), Some("..###"));
+        // An empty cell leaves too little room on one side.
+        assert_eq!(solve(&[3], "?.???").as_deref(), Some("..###"));
+    }
+
+    #[test]
+    fn filled_cells_cannot_be_skipped() {
+        // The first block can't start past the filled cell, so it covers it
+        // and the second block has to come later.
+        assert_eq!(solve(&[1, 1], "?#??").as_deref(), Some(".#.#"));
+        assert_eq!(leftmost(&[1, 1], &line("?#??")), Some(vec![1, 3]));
+    }
+
+    #[test]
+    fn contradictions_are_detected() {
+        // Every placement of the block covers the empty cell.
+        assert_eq!(solve(&[3], "??.??"), None);
+        // One block can't cover two filled cells with a gap between.
+        assert_eq!(solve(&[1], "#.#"), None);
+        // The blocks don't fit at all.
+        assert_eq!(solve(&[2, 2], "????"), None);
+        // A filled cell left over after the last block.
+        assert_eq!(solve(&[2], "##.#"), None);
+    }
+
+    #[test]
+    fn empty_clues_clear_the_line() {
+        assert_eq!(solve(&[], "???").as_deref(), Some("..."));
+        assert_eq!(solve(&[], "").as_deref(), Some(""));
+        assert_eq!(solve(&[], "?#?"), None);
+    }
+
+    #[test]
+    fn start_ranges_span_leftmost_to_rightmost() {
+        assert_eq!(
+            start_ranges(&[2, 1], &line("??????")),
+            Some(vec![(0, 2), (3, 5)])
+        );
+        assert_eq!(start_ranges(&[2], &line("##")), Some(vec![(0, 0)]));
+        assert_eq!(start_ranges(&[1], &line("?.#")), Some(vec![(2, 2)]));
+    }
+
+    // A square puzzle with every line's clues known.
+    fn puzzle(rows: &[&[usize]], cols: &[&[usize]]) -> NonogramPuzzle {
+        let clues = |lines: &[&[usize]]| {
+            lines
+                .iter()
+                .map(|line| Some(line.iter().map(|&n| Some(n)).collect()))
+                .collect()
+        };
+        NonogramPuzzle {
+            rows: rows.len(),
+            cols: cols.len(),
+            row_clues: clues(rows),
+            col_clues: clues(cols),
+        }
+    }
+
+    #[test]
+    fn prepass_reports_the_contradictory_line() {
+        // Both columns want a filled cell, but the second row wants none.
+        let puzzle = puzzle(&[&[2], &[]], &[&[2], &[2]]);
+        let mut known = vec![vec![None; 2]; 2];
+        assert_eq!(
+            line_prepass(&puzzle, &mut known, Deadline::NONE),
+            Err(Stopped::Contradiction("column 0".to_string()))
+        );
+    }
+
+    #[test]
+    fn prepass_past_its_deadline_is_not_a_fixpoint() {
+        let puzzle = puzzle(&[&[2], &[1]], &[&[2], &[1]]);
+        let mut known = vec![vec![None; 2]; 2];
+        assert_eq!(
+            line_prepass(&puzzle, &mut known, Deadline::after(Duration::ZERO)),
+            Err(Stopped::Deadline)
+        );
+        assert_eq!(line_prepass(&puzzle, &mut known, Deadline::NONE), Ok(()));
+        assert_eq!(known, vec![line("##"), line("#.")]);
+    }
+}
@@ -0,0 +1,262 @@
+use super::{exact_clues, LineClues, NonogramPuzzle};
+use std::fmt;
+
+// A structural problem with a puzzle, found before any solver is built.
+#[derive(Debug)]
+pub enum PuzzleError {
+    // The number of clue lists doesn't match the grid size.
+    ClueCount {
+        kind: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    // A block of length zero inside a clue list.
+    ZeroLengthClue {
+        line: String,
+        index: usize,
+    },
+    // The blocks plus the gaps between them don't fit in the line.
+    LineTooLong {
+        line: String,
+        needed: usize,
+        length: usize,
+    },
+    // Rows and columns must fill the same number of cells in total.
+    FilledMismatch {
+        rows: usize,
+        cols: usize,
+    },
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::ClueCount {
+                kind,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} {} clue lists but found {}",
+                expected, kind, found
+            ),
+            PuzzleError::ZeroLengthClue { line, index } => {
+                write!(f, "{}: clue {} has length zero", line, index)
+            }
+            PuzzleError::LineTooLong {
+                line,
+                needed,
+                length,
+            } => write!(
+                f,
+                "{}: clues need at least {} cells but the line only has {}",
+                line, needed, length
+            ),
+            PuzzleError::FilledMismatch { rows, cols } => write!(
+                f,
+                "row clues fill {} cells but column clues fill {}",
+                rows, cols
+            ),
+        }
+    }
+}
+
+// Checks the puzzle for every structural problem we can find without solving
+// it, rather than stopping at the first one.
+pub fn validate(puzzle: &NonogramPuzzle) -> Vec<PuzzleError> {
+    let mut errors = Vec::new();
+
+    if puzzle.row_clues.len() != puzzle.rows {
+        errors.push(PuzzleError::ClueCount {
+            kind: "row",
+            expected: puzzle.rows,
+            found: puzzle.row_clues.len(),
+        });
+    }
+    if puzzle.col_clues.len() != puzzle.cols {
+        errors.push(PuzzleError::ClueCount {
+            kind: "column",
+            expected: puzzle.cols,
+            found: puzzle.col_clues.len(),
+        });
+    }
+
+    let rows = check_lines("row", &puzzle.row_clues, puzzle.cols, &mut errors);
+    let cols = check_lines("column", &puzzle.col_clues, puzzle.rows, &mut errors);
+
+    // Totals can only be compared when no clue is a wildcard.
+    let all_known = puzzle
+        .row_clues
+        .iter()
+        .chain(puzzle.col_clues.iter())
+        .all(|clues| exact_clues(clues).is_some());
+    if all_known && rows != cols {
+        errors.push(PuzzleError::FilledMismatch { rows, cols });
+    }
+
+    errors
+}
+
+// Checks each clue list on its own, returning the total number of filled
+// cells the lines ask for. Wildcard blocks count as at least one cell, and
+// lines whose clues are entirely unknown can't be checked at all.
+fn check_lines(
+    kind: &str,
+    lines: &[LineClues],
+    length: usize,
+    errors: &mut Vec<PuzzleError>,
+) -> usize {
+    let mut total = 0;
+    for (i, clues) in lines.iter().enumerate() {
+        let Some(clues) = clues else {
+            continue;
+        };
+        let line = format!("{} {}", kind, i);
+
+        for (index, &block) in clues.iter().enumerate() {
+            if block == Some(0) {
+                errors.push(PuzzleError::ZeroLengthClue {
+                    line: line.clone(),
+                    index,
+                });
+            }
+        }
+
+        // Each block plus a single gap between neighbours.
+        let filled: usize = clues.iter().map(|block| block.unwrap_or(1)).sum();
+        let needed = filled + clues.len().saturating_sub(1);
+        if needed > length {
+            errors.push(PuzzleError::LineTooLong {
+                line,
+                needed,
+                length,
+            });
+        }
+
+        total += filled;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate, PuzzleError};
+    use crate::nonogram::{LineClues, NonogramPuzzle};
+
+    fn puzzle(
+        rows: usize,
+        cols: usize,
+        row_clues: Vec<LineClues>,
+        col_clues: Vec<LineClues>,
+    ) -> NonogramPuzzle {
+        NonogramPuzzle {
+            rows,
+            cols,
+            row_clues,
+            col_clues,
+        }
+    }
+
+    fn exact(clues: &[usize]) -> LineClues {
+        Some(clues.iter().map(|&len| Some(len)).collect())
+    }
+
+    #[test]
+    fn a_consistent_puzzle_passes() {
+        let p = puzzle(
+            2,
+            2,
+            vec![exact(&[2]), exact(&[1])],
+            vec![exact(&[2]), exact(&[1])],
+        );
+        assert!(validate(&p).is_empty());
+    }
+
+    #[test]
+    fn clue_counts_must_match_the_grid() {
+        let p = puzzle(3, 1, vec![exact(&[1]), exact(&[])], vec![exact(&[1])]);
+        let errors = validate(&p);
+        assert!(matches!(
+            errors[..],
+            [PuzzleError::ClueCount {
+                kind: "row",
+                expected: 3,
+                found: 2
+            }]
+        ));
+        assert_eq!(
+            errors[0].to_string(),
+            "expected 3 row clue lists but found 2"
+        );
+    }
+
+    #[test]
+    fn zero_length_blocks_are_rejected() {
+        let p = puzzle(
+            1,
+            3,
+            vec![exact(&[1, 0])],
+            vec![exact(&[1]), exact(&[]), exact(&[])],
+        );
+        let errors = validate(&p);
+        assert!(matches!(
+            &errors[..],
+            [PuzzleError::ZeroLengthClue { line, index: 1 }] if line == "row 0"
+        ));
+    }
+
+    #[test]
+    fn clues_must_fit_their_line() {
+        // Two blocks of two need a gap, so five cells.
+        let p = puzzle(4, 1, vec![exact(&[1]); 4], vec![exact(&[2, 2])]);
+        let errors = validate(&p);
+        assert!(matches!(
+            &errors[..],
+            [PuzzleError::LineTooLong { line, needed: 5, length: 4 }] if line == "column 0"
+        ));
+    }
+
+    #[test]
+    fn rows_and_columns_must_fill_the_same_cells() {
+        let p = puzzle(
+            2,
+            2,
+            vec![exact(&[2]), exact(&[2])],
+            vec![exact(&[1]), exact(&[1])],
+        );
+        let errors = validate(&p);
+        assert!(matches!(
+            errors[..],
+            [PuzzleError::FilledMismatch { rows: 4, cols: 2 }]
+        ));
+    }
+
+    #[test]
+    fn wildcards_skip_the_totals_but_not_the_fit() {
+        // The totals can't be compared once a block is unknown, but a
+        // wildcard block still takes at least one cell.
+        let p = puzzle(
+            1,
+            4,
+            vec![Some(vec![Some(2), None])],
+            vec![None, exact(&[1]), exact(&[1]), exact(&[1])],
+        );
+        assert!(validate(&p).is_empty());
+        let p = puzzle(
+            1,
+            3,
+            vec![Some(vec![Some(2), None, None])],
+            vec![None, None, None],
+        );
+        assert!(matches!(
+            validate(&p)[..],
+            [PuzzleError::LineTooLong { needed: 6, .. }]
+        ));
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let p = puzzle(1, 2, vec![exact(&[0, 3])], vec![exact(&[1])]);
+        assert_eq!(validate(&p).len(), 4);
+    }
+}
@@ -0,0 +1,124 @@
+use super::validate::Checked;
+use std::slice;
+use z3::ast::{Bool, Int};
+use z3::{Optimize, SatResult, Solver};
+
+// Why no restaurant fits, as worked out by `explain`.
+pub struct Diagnosis {
+    // What stops each restaurant, in order.
+    pub verdicts: Vec<Verdict>,
+    // The smallest budget increase that lets a restaurant through, and which
+    // restaurant that is. Err(Unsat) when the dietary rules rule out every
+    // one, Err(Unknown) when Z3 couldn't work it out.
+    pub raise: Result<(i64, usize), SatResult>,
+}
+
+// What `explain` found for one restaurant.
+pub enum Verdict {
+    // Nothing stops it.
+    Fits,
+    // These rules rule it out.
+    RuledOut(Vec<String>),
+    // Z3 couldn't decide.
+    Unknown,
+}
+
+// Explains why no restaurant fits: which rule rules out each one, and how
+// much more budget would let at least one of them through.
+pub fn explain(data: &Checked) -> Diagnosis {
+    let solver = Solver::new();
+    let people = data.people.len() as i64;
+
+    // One-hot encoding: Boolean variable for each restaurant
+    let is_chosen: Vec<Bool> = (0..data.restaurants.len())
+        .map(|i| Bool::new_const(format!("restaurant_{}", i)))
+        .collect();
+    let constraints: Vec<(&Bool, i32)> = is_chosen.iter().map(|b| (b, 1)).collect();
+    solver.assert(Bool::pb_eq(&constraints, 1));
+
+    // Every budget and dietary rule gets a tracker, so the unsat core can
+    // tell us which ones got in the way. `rules` maps trackers to messages.
+    let mut rules: Vec<(Bool, String)> = Vec::new();
+    for (i, restaurant) in data.restaurants.iter().enumerate() {
+        let total_cost = restaurant.cost * people;
+        let tracker = Bool::new_const(format!("budget_{}", i));
+        solver.assert_and_track(
+            is_chosen[i].implies(Int::from_i64(total_cost).le(data.budget)),
+            &tracker,
+        );
+        rules.push((
+            tracker,
+            format!(
+                "costs ${} for {} people, over the ${} budget",
+                total_cost, people, data.budget
+            ),
+        ));
+
+        if data.excluded(i) {
+            let tracker = Bool::new_const(format!("diet_{}", i));
+            solver.assert_and_track(is_chosen[i].not(), &tracker);
+            let tags = restaurant.tags();
+            let who: Vec<String> = data
+                .people
+                .iter()
+                .filter(|p| !p.can_eat_at(restaurant))
+                .map(|p| {
+                    let missing: Vec<String> = p.needs().difference(&tags).cloned().collect();
+                    format!("{} ({})", p.name, missing.join(", "))
+                })
+                .collect();
+            rules.push((tracker, format!("can't cater for {}", who.join(", "))));
+        }
+    }
+
+    // Try each restaurant in turn; the core holds the rules that stop it.
+    let verdicts = is_chosen
+        .iter()
+        .map(
+            |chosen| match solver.check_assumptions(slice::from_ref(chosen)) {
+                SatResult::Sat => Verdict::Fits,
+                SatResult::Unknown => Verdict::Unknown,
+                SatResult::Unsat => {
+                    let core = solver.get_unsat_core();
+                    let reasons = rules
+                        .iter()
+                        .filter(|(tracker, _)| core.contains(tracker))
+                        .map(|(_, message)| message.clone())
+                        .collect();
+                    Verdict::RuledOut(reasons)
+                }
+            },
+        )
+        .collect();
+
+    // Let the budget grow by `extra` and find the smallest `extra` that
+    // works. Dietary exclusions stay hard, since money won't fix those.
+    let opt = Optimize::new();
+    let extra = Int::new_const("extra_budget");
+    opt.assert(&extra.ge(0));
+    opt.assert(&Bool::pb_eq(&constraints, 1));
+    let budget = &extra + data.budget;
+    for (i, restaurant) in data.restaurants.iter().enumerate() {
+        let total_cost = restaurant.cost * people;
+        opt.assert(&is_chosen[i].implies(Int::from_i64(total_cost).le(&budget)));
+        if data.excluded(i) {
+            opt.assert(&is_chosen[i].not());
+        }
+    }
+    opt.minimize(&extra);
+
+    let raise = match opt.check(&[]) {
+        SatResult::Sat => {
+            let model = opt.get_model().unwrap();
+            let extra = numerals::eval_i64(&model, &extra);
+            let chosen_idx = is_chosen
+                .iter()
+                .position(|var| model.eval(var, true).unwrap().as_bool().unwrap())
+                .unwrap();
+            Ok((extra, chosen_idx))
+        }
+        result => Err(result),
+    };
+
+    Diagnosis { verdicts, raise }
+}
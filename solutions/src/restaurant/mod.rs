@@ -0,0 +1,182 @@
+pub mod diagnose;
+pub mod model;
+pub mod objective;
+pub mod pareto;
+pub mod plan;
+pub mod rank;
+pub mod split;
+pub mod validate;
+
+use objective::Objective;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use validate::Checked;
+use z3::ast::{Bool, Int};
+use z3::SatResult;
+
+#[derive(Deserialize, Debug)]
+pub struct InputData {
+    pub budget: i64,
+    pub restaurants: Vec<Restaurant>,
+    pub people: Vec<Person>,
+    // Plan this many nights instead of a single dinner.
+    #[serde(default)]
+    pub nights: Option<usize>,
+    // A budget for each night, or none to use `budget` every night.
+    #[serde(default)]
+    pub night_budgets: Vec<i64>,
+    // How many nights anyone can be sent to their worst choice (default 1).
+    #[serde(default)]
+    pub max_worst_nights: Option<usize>,
+    // What happens when a restaurant can't cater for someone.
+    #[serde(default)]
+    pub diet_rule: DietRule,
+    // Split the party across several restaurants instead of one.
+    #[serde(default)]
+    pub split: Option<split::SplitRules>,
+}
+
+impl InputData {
+    // Whether restaurant `i` is ruled out because someone can't eat there.
+    fn excluded(&self, i: usize) -> bool {
+        self.diet_rule == DietRule::Exclude
+            && self
+                .people
+                .iter()
+                .any(|p| !p.can_eat_at(&self.restaurants[i]))
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DietRule {
+    // The person comes along but their rating counts as 0.
+    #[default]
+    Zero,
+    // The restaurant can't be chosen at all.
+    Exclude,
+}
+
+// Dietary tags are free-form (vegan, vegetarian, gluten-free, halal,
+// nut-free, ...) and compared case-insensitively. The old `vegan` and
+// `is_vegan` booleans still work and just add the "vegan" tag.
+#[derive(Deserialize, Debug)]
+pub struct Restaurant {
+    pub name: String,
+    pub cost: i64,
+    #[serde(default)]
+    vegan: bool,
+    // Diets the kitchen can cater for.
+    #[serde(default)]
+    accommodates: Vec<String>,
+    // Group size limits when the party is split (see `split::SplitRules`).
+    #[serde(default)]
+    min_group: Option<usize>,
+    #[serde(default)]
+    max_group: Option<usize>,
+}
+
+impl Restaurant {
+    fn tags(&self) -> BTreeSet<String> {
+        let mut tags: BTreeSet<String> =
+            self.accommodates.iter().map(|t| t.to_lowercase()).collect();
+        if self.vegan {
+            tags.insert("vegan".to_string());
+        }
+        // Anywhere that can do vegan can do vegetarian.
+        if tags.contains("vegan") {
+            tags.insert("vegetarian".to_string());
+        }
+        tags
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Person {
+    pub name: String,
+    #[serde(default)]
+    is_vegan: bool,
+    // Diets this person needs catered for.
+    #[serde(default)]
+    requires: Vec<String>,
+    // As read, see `Ratings`.
+    #[serde(rename = "ratings")]
+    raw_ratings: Ratings,
+    // One rating per restaurant, in order. Filled in by `validate::check`,
+    // which is why the solvers only take `validate::Checked` input.
+    #[serde(skip)]
+    ratings: Vec<i64>,
+}
+
+// Ratings can be a list in restaurant order, or keyed by restaurant name.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Ratings {
+    List(Vec<i64>),
+    ByName(BTreeMap<String, i64>),
+}
+
+impl Person {
+    fn needs(&self) -> BTreeSet<String> {
+        let mut needs: BTreeSet<String> = self.requires.iter().map(|t| t.to_lowercase()).collect();
+        if self.is_vegan {
+            needs.insert("vegan".to_string());
+        }
+        needs
+    }
+
+    pub fn can_eat_at(&self, restaurant: &Restaurant) -> bool {
+        self.needs().is_subset(&restaurant.tags())
+    }
+
+    // How happy this person is at restaurant `i`. Someone who can't eat at a
+    // restaurant gets nothing out of it.
+    pub fn happiness(&self, restaurants: &[Restaurant], i: usize) -> i64 {
+        if self.can_eat_at(&restaurants[i]) {
+            self.ratings[i]
+        } else {
+            0
+        }
+    }
+}
+
+// Each person's happiness as a Z3 term, given which restaurant is chosen.
+fn happiness_terms(data: &InputData, is_chosen: &[Bool]) -> Vec<Int> {
+    data.people
+        .iter()
+        .map(|person| {
+            let terms: Vec<Int> = is_chosen
+                .iter()
+                .enumerate()
+                .map(|(i, b)| {
+                    let happiness = person.happiness(&data.restaurants, i);
+                    b.ite(&Int::from_i64(happiness), &Int::from_i64(0))
+                })
+                .collect();
+            let refs: Vec<&Int> = terms.iter().collect();
+            Int::add(&refs)
+        })
+        .collect()
+}
+
+// The highest rating anyone gives, which bounds one night's happiness.
+fn max_rating(data: &InputData) -> i64 {
+    data.people
+        .iter()
+        .flat_map(|p| p.ratings.iter().copied())
+        .max()
+        .unwrap_or(0)
+}
+
+// Picks the one restaurant that's best under `objective`. Err holds the
+// check result when there isn't one: Unsat if nothing fits, Unknown if Z3
+// gave up or was interrupted.
+pub fn choose(data: &Checked, objective: Objective) -> Result<usize, SatResult> {
+    let model = model::single(data, true);
+    model.apply(objective);
+
+    match model.opt.check(&[]) {
+        SatResult::Sat => Ok(model.decode()[0]),
+        result => Err(result),
+    }
+}
@@ -0,0 +1,102 @@
+use super::objective::Objective;
+use super::{happiness_terms, max_rating, validate::Checked};
+use z3::ast::{Bool, Int};
+use z3::Optimize;
+
+// A restaurant problem as Z3 terms, ready for an objective. Every mode reads
+// its answer back as the restaurant each person eats at.
+pub struct Model {
+    pub opt: Optimize,
+    // assigned[p][i]: person p eats at restaurant i. When the whole party
+    // eats together every row is the same one-hot vector.
+    pub assigned: Vec<Vec<Bool>>,
+    // Each person's happiness, and their sum
+    pub people: Vec<Int>,
+    pub total_happiness: Int,
+    // What the party spends in total
+    pub total_cost: Int,
+    // An upper bound on anyone's happiness
+    pub max: i64,
+}
+
+impl Model {
+    pub fn apply(&self, objective: Objective) -> Vec<Int> {
+        objective.apply(&self.opt, &self.people, &self.total_happiness, self.max)
+    }
+
+    // Reads back where each person eats. Only valid after a Sat check.
+    pub fn decode(&self) -> Vec<usize> {
+        let model = self.opt.get_model().unwrap();
+        self.assigned
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .position(|var| model.eval(var, true).unwrap().as_bool().unwrap())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    // Forbids exactly this assignment, so the next check has to find another.
+    pub fn block(&self, assignment: &[usize]) {
+        let picked: Vec<&Bool> = self
+            .assigned
+            .iter()
+            .zip(assignment)
+            .map(|(row, &i)| &row[i])
+            .collect();
+        self.opt.assert(&Bool::and(&picked).not());
+    }
+
+    pub fn eval(&self, term: &Int) -> i64 {
+        let model = self.opt.get_model().unwrap();
+        numerals::eval_i64(&model, term)
+    }
+}
+
+// The whole party at one restaurant. With `budget` off, cost is left free so
+// other modes can trade it off against happiness.
+pub fn single(data: &Checked, budget: bool) -> Model {
+    let opt = Optimize::new();
+    let people = data.people.len() as i64;
+
+    // One-hot encoding: Boolean variable for each restaurant
+    let is_chosen: Vec<Bool> = (0..data.restaurants.len())
+        .map(|i| Bool::new_const(format!("restaurant_{}", i)))
+        .collect();
+
+    // Exactly one restaurant must be chosen
+    let constraints: Vec<(&Bool, i32)> = is_chosen.iter().map(|b| (b, 1)).collect();
+    // NOTE: This allows us to potentially pick the "best N". Here we choose 1.
+    opt.assert(&Bool::pb_eq(&constraints, 1));
+
+    let mut costs = Vec::with_capacity(data.restaurants.len());
+    for (i, restaurant) in data.restaurants.iter().enumerate() {
+        let total_cost = restaurant.cost * people;
+        costs.push(is_chosen[i].ite(&Int::from_i64(total_cost), &Int::from_i64(0)));
+
+        // Budget constraint: if a restaurant is chosen, its cost must be within budget
+        if budget {
+            opt.assert(&is_chosen[i].implies(Int::from_i64(total_cost).le(data.budget)));
+        }
+
+        // Dietary constraint: nobody goes where they can't eat
+        if data.excluded(i) {
+            opt.assert(&is_chosen[i].not());
+        }
+    }
+    let cost_refs: Vec<&Int> = costs.iter().collect();
+
+    // Everyone's happiness, and their sum
+    let happiness = happiness_terms(data, &is_chosen);
+    let happiness_refs: Vec<&Int> = happiness.iter().collect();
+
+    Model {
+        total_happiness: Int::add(&happiness_refs),
+        total_cost: Int::add(&cost_refs),
+        opt,
+        assigned: vec![is_chosen; data.people.len()],
+        people: happiness,
+        max: max_rating(data),
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+use z3::ast::Int;
+use z3::Optimize;
+
+// What "best" means when people disagree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    // Total happiness. One unhappy person can be outvoted.
+    Sum,
+    // The happiness of whoever is least happy.
+    Maximin,
+    // Maximin first, then the total among the choices that tie on it.
+    Lexicographic,
+    // The product of everyone's happiness, which punishes leaving anyone
+    // near zero. Approximated as a sum of logs, see `nash`.
+    Nash,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 4] = [
+        Objective::Sum,
+        Objective::Maximin,
+        Objective::Lexicographic,
+        Objective::Nash,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sum" => Some(Self::Sum),
+            "maximin" => Some(Self::Maximin),
+            "lex" => Some(Self::Lexicographic),
+            "nash" => Some(Self::Nash),
+            _ => None,
+        }
+    }
+
+    // Adds this objective to `opt`. `people` holds each person's happiness,
+    // `total` their sum, and `max` an upper bound on anyone's happiness.
+    // Returns the terms being maximized, most important first.
+    pub fn apply(&self, opt: &Optimize, people: &[Int], total: &Int, max: i64) -> Vec<Int> {
+        let terms = match self {
+            Self::Sum => vec![total.clone()],
+            Self::Maximin => vec![maximin(opt, people)],
+            // Optimize treats objectives lexicographically in the order
+            // they are added.
+            Self::Lexicographic => vec![maximin(opt, people), total.clone()],
+            Self::Nash => vec![nash(opt, people, max)],
+        };
+        for term in &terms {
+            opt.maximize(term);
+        }
+        terms
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sum => write!(f, "sum"),
+            Self::Maximin => write!(f, "maximin"),
+            Self::Lexicographic => write!(f, "lex"),
+            Self::Nash => write!(f, "nash"),
+        }
+    }
+}
+
+// A new variable that can't exceed anyone's happiness, so maximizing it
+// maximizes the minimum.
+fn maximin(opt: &Optimize, people: &[Int]) -> Int {
+    let min = Int::new_const("min_happiness");
+    for happiness in people {
+        opt.assert(&min.le(happiness));
+    }
+    min
+}
+
+// Fixed-point scale for the logs below.
+const LOG_SCALE: f64 = 1000.0;
+
+// Maximizing a product is maximizing the sum of logs, but Z3 can't take a
+// log. Since log is concave, each person's term is capped by the chords of
+// 1000·ln(1 + h) between h = 0, 1, 2, 4, 8, ..., so it can only reach the
+// piecewise-linear curve through those points. The log isn't defined for
+// negative happiness, so below zero the first chord carries on as a straight
+// line and every point of unhappiness costs as much as the first point of
+// happiness gains. There is always at least that one chord, even when nobody
+// rates anything above zero, and the term is capped at the last point too, so
+// the objective is never unbounded.
+fn nash(opt: &Optimize, people: &[Int], max: i64) -> Int {
+    let mut points = vec![0, 1];
+    while *points.last().unwrap() < max {
+        points.push(points.last().unwrap() * 2);
+    }
+    points.push(points.last().unwrap() * 2);
+    let log = |h: i64| (LOG_SCALE * (1.0 + h as f64).ln()).round() as i64;
+    let top = log(*points.last().unwrap());
+
+    let terms: Vec<Int> = people
+        .iter()
+        .enumerate()
+        .map(|(p, happiness)| {
+            let term = Int::new_const(format!("log_happiness_{}", p));
+            for pair in points.windows(2) {
+                // term <= f(a) + (f(b) - f(a)) / (b - a) * (h - a), with
+                // both sides multiplied by (b - a) to stay in integers.
+                let (a, b) = (pair[0], pair[1]);
+                let lhs = &term * (b - a);
+                let rhs = &(happiness - a) * (log(b) - log(a)) + log(a) * (b - a);
+                opt.assert(&lhs.le(&rhs));
+            }
+            opt.assert(&term.le(top));
+            term
+        })
+        .collect();
+    let refs: Vec<&Int> = terms.iter().collect();
+    Int::add(&refs)
+}
@@ -0,0 +1,55 @@
+use super::model::{self, Model};
+use super::{split, validate::Checked};
+use z3::SatResult;
+
+// One non-dominated trade-off: nothing cheaper makes the party as happy.
+pub struct Point {
+    pub cost: i64,
+    pub happiness: i64,
+    pub assignment: Vec<usize>,
+}
+
+// The front, cheapest point first, as found by `front`.
+pub struct Front {
+    pub points: Vec<Point>,
+    // Z3 gave up partway, so cheaper points than these may be missing.
+    pub partial: bool,
+}
+
+// Walks the cost/happiness front from the most expensive end down. Each step
+// asks for the happiest plan strictly cheaper than the last, and the cheapest
+// among those, so every point found is non-dominated. The budget is ignored
+// so planners can see what going over it would buy.
+pub fn front(data: &Checked) -> Front {
+    let model = if data.split.is_some() {
+        split::build(data, false).unwrap()
+    } else {
+        model::single(data, false)
+    };
+    model.opt.maximize(&model.total_happiness);
+    model.opt.minimize(&model.total_cost);
+
+    let mut points = Vec::new();
+    let result = loop {
+        match model.opt.check(&[]) {
+            SatResult::Sat => {}
+            result => break result,
+        }
+        let point = read(&model);
+        model.opt.assert(&model.total_cost.lt(point.cost));
+        points.push(point);
+    };
+    points.reverse();
+    Front {
+        points,
+        partial: result == SatResult::Unknown,
+    }
+}
+
+fn read(model: &Model) -> Point {
+    Point {
+        cost: model.eval(&model.total_cost),
+        happiness: model.eval(&model.total_happiness),
+        assignment: model.decode(),
+    }
+}
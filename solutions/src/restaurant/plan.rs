@@ -0,0 +1,124 @@
+use super::objective::Objective;
+use super::{happiness_terms, max_rating, validate::Checked};
+use z3::ast::{Bool, Int};
+use z3::{Optimize, SatResult};
+
+// No restaurant may come up twice in any run of this many nights.
+const WEEK: usize = 7;
+
+// Picks a restaurant for each of `nights` nights, best under `objective`
+// with everyone's happiness summed over the nights. Err holds the check
+// result when there's no schedule, as for `choose`.
+pub fn schedule(
+    data: &Checked,
+    nights: usize,
+    objective: Objective,
+) -> Result<Vec<usize>, SatResult> {
+    let opt = Optimize::new();
+    let people = data.people.len() as i64;
+    let max_worst = data.max_worst_nights.unwrap_or(1);
+
+    // chosen[n][i]: restaurant i is where we eat on night n.
+    let chosen: Vec<Vec<Bool>> = (0..nights)
+        .map(|n| {
+            (0..data.restaurants.len())
+                .map(|i| Bool::new_const(format!("night_{}_restaurant_{}", n, i)))
+                .collect()
+        })
+        .collect();
+
+    for (n, night) in chosen.iter().enumerate() {
+        // Exactly one restaurant each night
+        let one_hot: Vec<(&Bool, i32)> = night.iter().map(|b| (b, 1)).collect();
+        opt.assert(&Bool::pb_eq(&one_hot, 1));
+
+        // That night's budget has to cover everyone
+        let budget = data.night_budgets.get(n).copied().unwrap_or(data.budget);
+        for (i, restaurant) in data.restaurants.iter().enumerate() {
+            let total_cost = restaurant.cost * people;
+            opt.assert(&night[i].implies(Int::from_i64(total_cost).le(budget)));
+            if data.excluded(i) {
+                opt.assert(&night[i].not());
+            }
+        }
+    }
+
+    // No repeats within a week: every window of WEEK nights uses a
+    // restaurant at most once.
+    for start in 0..nights.saturating_sub(WEEK - 1).max(1) {
+        let window = &chosen[start..(start + WEEK).min(nights)];
+        for i in 0..data.restaurants.len() {
+            let visits: Vec<(&Bool, i32)> = window.iter().map(|night| (&night[i], 1)).collect();
+            opt.assert(&Bool::pb_le(&visits, 1));
+        }
+    }
+
+    for (person, worst) in data.people.iter().zip(worst_choices(data)) {
+        let Some(worst) = worst else {
+            continue;
+        };
+        let happiness: Vec<i64> = (0..data.restaurants.len())
+            .map(|i| person.happiness(&data.restaurants, i))
+            .collect();
+
+        let worst_nights: Vec<(&Bool, i32)> = chosen
+            .iter()
+            .flat_map(|night| {
+                night
+                    .iter()
+                    .zip(&happiness)
+                    .filter(|(_, &h)| h == worst)
+                    .map(|(b, _)| (b, 1))
+            })
+            .collect();
+        opt.assert(&Bool::pb_le(&worst_nights, max_worst as i32));
+    }
+
+    // Each person's happiness summed over the nights
+    let nightly: Vec<Vec<Int>> = chosen
+        .iter()
+        .map(|night| happiness_terms(data, night))
+        .collect();
+    let people: Vec<Int> = (0..data.people.len())
+        .map(|p| {
+            let refs: Vec<&Int> = nightly.iter().map(|night| &night[p]).collect();
+            Int::add(&refs)
+        })
+        .collect();
+    let people_refs: Vec<&Int> = people.iter().collect();
+    let total_happiness = Int::add(&people_refs);
+
+    let max = max_rating(data) * nights as i64;
+    objective.apply(&opt, &people, &total_happiness, max);
+
+    match opt.check(&[]) {
+        SatResult::Sat => {}
+        result => return Err(result),
+    }
+
+    let model = opt.get_model().unwrap();
+    let schedule = chosen
+        .iter()
+        .map(|night| {
+            night
+                .iter()
+                .position(|var| model.eval(var, true).unwrap().as_bool().unwrap())
+                .unwrap()
+        })
+        .collect();
+    Ok(schedule)
+}
+
+// A person's worst choices are the restaurants they'd be least happy at.
+// Someone who likes everything equally has no worst choice.
+pub fn worst_choices(data: &Checked) -> Vec<Option<i64>> {
+    data.people
+        .iter()
+        .map(|person| {
+            let happiness =
+                (0..data.restaurants.len()).map(|i| person.happiness(&data.restaurants, i));
+            let (worst, best) = (happiness.clone().min()?, happiness.max()?);
+            (worst < best).then_some(worst)
+        })
+        .collect()
+}
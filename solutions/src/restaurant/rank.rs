@@ -0,0 +1,67 @@
+use super::model::{self, Model};
+use super::objective::Objective;
+use super::{split, validate::Checked};
+use z3::SatResult;
+
+// One of the best choices, with the objective's terms as it scored them.
+pub struct Pick {
+    pub score: Vec<i64>,
+    pub cost: i64,
+    pub happiness: i64,
+    pub assignment: Vec<usize>,
+}
+
+// The best choices, best first, as found by `top`.
+pub struct Ranking {
+    pub picks: Vec<Pick>,
+    // Z3 gave up looking for the next pick, so there may be more than these,
+    // including ones that tie with the last.
+    pub unknown: bool,
+}
+
+// Finds the best `n` choices under `objective`, best first. Choices that tie
+// with the nth are listed too rather than cut off arbitrarily, so there can
+// be more than `n`. Each round blocks every earlier pick inside a push/pop
+// scope and re-optimizes, so the solver is left as it was for the next round.
+pub fn top(data: &Checked, objective: Objective, n: usize) -> Ranking {
+    let model = if data.split.is_some() {
+        split::build(data, true).unwrap()
+    } else {
+        model::single(data, true)
+    };
+    let terms = model.apply(objective);
+
+    let mut picks: Vec<Pick> = Vec::new();
+    loop {
+        model.opt.push();
+        for pick in &picks {
+            model.block(&pick.assignment);
+        }
+        let result = model.opt.check(&[]);
+        let next = (result == SatResult::Sat).then(|| read(&model, &terms));
+        model.opt.pop();
+
+        match next {
+            Some(pick)
+                if picks.len() < n || picks.last().is_some_and(|last| last.score == pick.score) =>
+            {
+                picks.push(pick)
+            }
+            _ => {
+                return Ranking {
+                    picks,
+                    unknown: result == SatResult::Unknown,
+                }
+            }
+        }
+    }
+}
+
+fn read(model: &Model, terms: &[z3::ast::Int]) -> Pick {
+    Pick {
+        score: terms.iter().map(|term| model.eval(term)).collect(),
+        cost: model.eval(&model.total_cost),
+        happiness: model.eval(&model.total_happiness),
+        assignment: model.decode(),
+    }
+}
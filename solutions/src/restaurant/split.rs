@@ -0,0 +1,165 @@
+use super::model::Model;
+use super::objective::Objective;
+use super::{max_rating, validate::Checked, DietRule};
+use serde::Deserialize;
+use z3::ast::{Bool, Int};
+use z3::{Optimize, SatResult};
+
+// Rules for splitting the party across several restaurants.
+#[derive(Deserialize, Debug)]
+pub struct SplitRules {
+    // Smallest group worth sending anywhere.
+    #[serde(default = "default_min_group")]
+    pub min_group: usize,
+    // Largest group any one restaurant will seat, unless it says otherwise.
+    #[serde(default)]
+    pub max_group: Option<usize>,
+    // How many restaurants we're willing to spread over.
+    #[serde(default)]
+    pub max_venues: Option<usize>,
+    // Pairs of names that must eat at the same restaurant.
+    #[serde(default)]
+    pub together: Vec<(String, String)>,
+}
+
+fn default_min_group() -> usize {
+    1
+}
+
+impl Default for SplitRules {
+    fn default() -> Self {
+        Self {
+            min_group: default_min_group(),
+            max_group: None,
+            max_venues: None,
+            together: Vec::new(),
+        }
+    }
+}
+
+// Assigns every person to a restaurant, best under `objective`, and returns
+// the restaurant index for each person. Err holds the check result when
+// there's no assignment, as for `choose`; without split rules that's Unsat.
+pub fn assign(data: &Checked, objective: Objective) -> Result<Vec<usize>, SatResult> {
+    let Some(model) = build(data, true) else {
+        return Err(SatResult::Unsat);
+    };
+    model.apply(objective);
+
+    match model.opt.check(&[]) {
+        SatResult::Sat => Ok(model.decode()),
+        result => Err(result),
+    }
+}
+
+// The split problem as a model, None without split rules. With `budget` off,
+// the total spend is left free.
+pub fn build(data: &Checked, budget: bool) -> Option<Model> {
+    let rules = data.split.as_ref()?;
+    let opt = Optimize::new();
+
+    // assigned[p][i]: person p eats at restaurant i.
+    let assigned: Vec<Vec<Bool>> = (0..data.people.len())
+        .map(|p| {
+            (0..data.restaurants.len())
+                .map(|i| Bool::new_const(format!("person_{}_restaurant_{}", p, i)))
+                .collect()
+        })
+        .collect();
+
+    for (person, row) in data.people.iter().zip(&assigned) {
+        // Everyone eats at exactly one restaurant
+        let one_hot: Vec<(&Bool, i32)> = row.iter().map(|b| (b, 1)).collect();
+        opt.assert(&Bool::pb_eq(&one_hot, 1));
+
+        // Dietary constraint: with the exclude rule nobody goes where they
+        // can't eat. Now that's decided per person, not for the party.
+        if data.diet_rule == DietRule::Exclude {
+            for (i, restaurant) in data.restaurants.iter().enumerate() {
+                if !person.can_eat_at(restaurant) {
+                    opt.assert(&row[i].not());
+                }
+            }
+        }
+    }
+
+    // Group sizes: a restaurant we use gets between its minimum and maximum.
+    let mut used = Vec::with_capacity(data.restaurants.len());
+    for (i, restaurant) in data.restaurants.iter().enumerate() {
+        let column: Vec<(&Bool, i32)> = assigned.iter().map(|row| (&row[i], 1)).collect();
+        let is_used = Bool::new_const(format!("restaurant_{}_used", i));
+        let diners: Vec<&Bool> = assigned.iter().map(|row| &row[i]).collect();
+        opt.assert(&is_used.eq(Bool::or(&diners)));
+
+        let min = restaurant.min_group.unwrap_or(rules.min_group);
+        opt.assert(&is_used.implies(Bool::pb_ge(&column, min as i32)));
+        if let Some(max) = restaurant.max_group.or(rules.max_group) {
+            opt.assert(&Bool::pb_le(&column, max as i32));
+        }
+        used.push(is_used);
+    }
+
+    // Spread over no more than `max_venues` restaurants
+    if let Some(max_venues) = rules.max_venues {
+        let venues: Vec<(&Bool, i32)> = used.iter().map(|b| (b, 1)).collect();
+        opt.assert(&Bool::pb_le(&venues, max_venues as i32));
+    }
+
+    // Pairs that stay together sit at the same restaurant
+    for (a, b) in &rules.together {
+        let index = |name: &str| {
+            data.people
+                .iter()
+                .position(|p| p.name == *name)
+                .unwrap_or_else(|| panic!("Unknown person in split.together: {}", name))
+        };
+        let (a, b) = (index(a), index(b));
+        for (x, y) in assigned[a].iter().zip(&assigned[b]) {
+            opt.assert(&x.eq(y));
+        }
+    }
+
+    // Budget constraint: the whole party's spend
+    let spend: Vec<Int> = assigned
+        .iter()
+        .flat_map(|row| {
+            row.iter()
+                .zip(&data.restaurants)
+                .map(|(b, restaurant)| b.ite(&Int::from_i64(restaurant.cost), &Int::from_i64(0)))
+        })
+        .collect();
+    let spend_refs: Vec<&Int> = spend.iter().collect();
+    let total_cost = Int::add(&spend_refs);
+    if budget {
+        opt.assert(&total_cost.le(data.budget));
+    }
+
+    // Everyone's happiness where they end up, and their sum
+    let people: Vec<Int> = data
+        .people
+        .iter()
+        .zip(&assigned)
+        .map(|(person, row)| {
+            let terms: Vec<Int> = row
+                .iter()
+                .enumerate()
+                .map(|(i, b)| {
+                    let happiness = person.happiness(&data.restaurants, i);
+                    b.ite(&Int::from_i64(happiness), &Int::from_i64(0))
+                })
+                .collect();
+            let refs: Vec<&Int> = terms.iter().collect();
+            Int::add(&refs)
+        })
+        .collect();
+    let people_refs: Vec<&Int> = people.iter().collect();
+
+    Some(Model {
+        total_happiness: Int::add(&people_refs),
+        total_cost,
+        opt,
+        assigned,
+        people,
+        max: max_rating(data),
+    })
+}
@@ -0,0 +1,140 @@
+use super::{InputData, Ratings};
+use std::collections::BTreeSet;
+use std::ops::Deref;
+
+// Input that passed `check`, with every person's ratings filled in. The
+// solvers only take this, so none of them can be handed unchecked input.
+pub struct Checked(InputData);
+
+impl Deref for Checked {
+    type Target = InputData;
+
+    fn deref(&self) -> &InputData {
+        &self.0
+    }
+}
+
+// Checks the input hangs together before anything reaches the solver, and
+// fills in each person's positional ratings. Err holds every problem found,
+// naming the person or restaurant involved, rather than stopping at the
+// first.
+pub fn check(mut data: InputData) -> Result<Checked, Vec<String>> {
+    let mut problems = Vec::new();
+
+    if data.budget < 0 {
+        problems.push(format!("budget is negative (${})", data.budget));
+    }
+    for (n, budget) in data.night_budgets.iter().enumerate() {
+        if *budget < 0 {
+            problems.push(format!("night {} budget is negative (${})", n + 1, budget));
+        }
+    }
+    match data.nights {
+        Some(nights) if !data.night_budgets.is_empty() && data.night_budgets.len() != nights => {
+            problems.push(format!(
+                "night_budgets has {} budgets for {} nights",
+                data.night_budgets.len(),
+                nights
+            ))
+        }
+        None if !data.night_budgets.is_empty() => {
+            problems.push("night_budgets is given without nights".to_string())
+        }
+        _ => {}
+    }
+
+    let mut names = BTreeSet::new();
+    for restaurant in &data.restaurants {
+        if !names.insert(restaurant.name.as_str()) {
+            problems.push(format!("restaurant {} is listed twice", restaurant.name));
+        }
+        if restaurant.cost < 0 {
+            problems.push(format!(
+                "restaurant {} has a negative cost (${})",
+                restaurant.name, restaurant.cost
+            ));
+        }
+        if let (Some(min), Some(max)) = (restaurant.min_group, restaurant.max_group) {
+            if min > max {
+                problems.push(format!(
+                    "restaurant {} has min_group {} above max_group {}",
+                    restaurant.name, min, max
+                ));
+            }
+        }
+    }
+
+    let mut people = BTreeSet::new();
+    for person in &data.people {
+        if !people.insert(person.name.as_str()) {
+            problems.push(format!("{} is listed twice", person.name));
+        }
+    }
+
+    for person in &mut data.people {
+        person.ratings = match &person.raw_ratings {
+            Ratings::List(ratings) => {
+                if ratings.len() != data.restaurants.len() {
+                    problems.push(format!(
+                        "{} has {} ratings for {} restaurants",
+                        person.name,
+                        ratings.len(),
+                        data.restaurants.len()
+                    ));
+                }
+                ratings.clone()
+            }
+            Ratings::ByName(ratings) => {
+                for name in ratings.keys() {
+                    if !names.contains(name.as_str()) {
+                        problems.push(format!(
+                            "{} rates {}, which isn't a restaurant",
+                            person.name, name
+                        ));
+                    }
+                }
+                data.restaurants
+                    .iter()
+                    .map(|restaurant| match ratings.get(&restaurant.name) {
+                        Some(&rating) => rating,
+                        None => {
+                            problems.push(format!(
+                                "{} has no rating for {}",
+                                person.name, restaurant.name
+                            ));
+                            0
+                        }
+                    })
+                    .collect()
+            }
+        };
+    }
+
+    if let Some(rules) = &data.split {
+        // A split plans one dinner, so there's nothing to spread over nights.
+        if data.nights.is_some() {
+            problems.push("split and nights can't be used together".to_string());
+        }
+        for (a, b) in &rules.together {
+            for name in [a, b] {
+                if !data.people.iter().any(|p| p.name == *name) {
+                    problems.push(format!("split.together names {}, who isn't coming", name));
+                }
+            }
+        }
+        if let Some(max) = rules.max_group {
+            if rules.min_group > max {
+                problems.push(format!(
+                    "split has min_group {} above max_group {}",
+                    rules.min_group, max
+                ));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(Checked(data))
+    } else {
+        Err(problems)
+    }
+}